@@ -0,0 +1,139 @@
+// 构建脚本：探测构建环境，输出自定义的 cfg 标志
+//
+// target_os 等内置条件只描述目标平台本身，而很多功能还取决于构建环境：
+// 内核头文件的版本、系统调用表、libc 的版本。这里在编译前检查这些信息，
+// 通过 `cargo:rustc-cfg` 输出标志，代码中就可以和 target_os 一起使用：
+//
+//     #[cfg(all(target_os = "linux", has_statx))]
+//     pub fn birth_time(path: &Path) -> io::Result<Option<SystemTime>> { ... }
+//
+// 输出的标志：
+//   has_io_uring  内核头文件提供 <linux/io_uring.h>（Linux 5.1+）
+//   has_statx     系统调用表中有 statx，且 glibc 提供了 statx() 包装函数（glibc 2.28+）
+//
+// 交叉编译时主机上的头文件不能代表目标系统，因此不做探测；
+// 这时可以用环境变量 CFG_MACRO_BUILD_CFGS 直接指定，例如 `CFG_MACRO_BUILD_CFGS=has_statx`，
+// 设为空字符串则关闭所有标志。
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 构建脚本可能输出的全部 cfg，都要通过 rustc-check-cfg 声明，拼错的 cfg 才会得到警告
+const KNOWN_CFGS: &[&str] = &["has_io_uring", "has_statx"];
+
+/// statx() 包装函数从 glibc 2.28 开始提供
+const STATX_MIN_GLIBC: (u32, u32) = (2, 28);
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=CFG_MACRO_BUILD_CFGS");
+    for cfg in KNOWN_CFGS {
+        println!("cargo:rustc-check-cfg=cfg({})", cfg);
+    }
+
+    let probe = Probe::new();
+    let cfgs = match env::var("CFG_MACRO_BUILD_CFGS") {
+        Ok(list) => from_override(&list),
+        Err(_) => probe.cfgs(),
+    };
+    for cfg in &cfgs {
+        println!("cargo:rustc-cfg={}", cfg);
+    }
+
+    // 探测到的 glibc 版本，库中通过 option_env! 读取
+    if let Some((major, minor)) = probe.glibc_version() {
+        println!("cargo:rustc-env=CFG_MACRO_GLIBC_VERSION={}.{}", major, minor);
+    }
+}
+
+/// 解析 CFG_MACRO_BUILD_CFGS：逗号分隔的 cfg 名字，未知的名字直接报错
+fn from_override(list: &str) -> Vec<&'static str> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            KNOWN_CFGS.iter().copied().find(|known| *known == name).unwrap_or_else(|| {
+                panic!(
+                    "unknown cfg `{}` in CFG_MACRO_BUILD_CFGS; expected one of {}",
+                    name,
+                    KNOWN_CFGS.join(", ")
+                )
+            })
+        })
+        .collect()
+}
+
+/// 对构建环境的探测，只在为本机构建 Linux 目标时进行
+struct Probe {
+    /// 头文件搜索目录：/usr/include 以及 Debian 风格的多架构目录
+    include_dirs: Vec<PathBuf>,
+}
+
+impl Probe {
+    fn new() -> Self {
+        let target = env::var("TARGET").unwrap_or_default();
+        let host = env::var("HOST").unwrap_or_default();
+        let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
+
+        let include_dirs = if target_os != "linux" {
+            Vec::new()
+        } else if target != host {
+            println!(
+                "cargo:warning=cross-compiling for {}; skipping header probes (set CFG_MACRO_BUILD_CFGS to choose cfgs)",
+                target
+            );
+            Vec::new()
+        } else {
+            let arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap_or_default();
+            let target_env = env::var("CARGO_CFG_TARGET_ENV").unwrap_or_default();
+            let multiarch = format!("/usr/include/{}-linux-{}", arch, target_env);
+            [PathBuf::from("/usr/include"), PathBuf::from(multiarch)]
+                .into_iter()
+                .filter(|dir| dir.is_dir())
+                .collect()
+        };
+        Self { include_dirs }
+    }
+
+    fn cfgs(&self) -> Vec<&'static str> {
+        let mut cfgs = Vec::new();
+        if self.header("linux/io_uring.h").is_some() {
+            cfgs.push("has_io_uring");
+        }
+        let statx_syscall = ["asm/unistd_64.h", "asm/unistd_32.h", "asm-generic/unistd.h"]
+            .iter()
+            .filter_map(|header| self.header(header))
+            .any(|path| read(&path).contains("__NR_statx"));
+        if statx_syscall && self.glibc_version().is_some_and(|version| version >= STATX_MIN_GLIBC) {
+            cfgs.push("has_statx");
+        }
+        cfgs
+    }
+
+    /// 在搜索目录中查找头文件，找到时登记 rerun-if-changed
+    fn header(&self, name: &str) -> Option<PathBuf> {
+        let path = self.include_dirs.iter().map(|dir| dir.join(name)).find(|path| path.is_file())?;
+        println!("cargo:rerun-if-changed={}", path.display());
+        Some(path)
+    }
+
+    /// 从 <features.h> 中的 __GLIBC__ / __GLIBC_MINOR__ 读取 glibc 版本；musl 等其他 libc 返回 None
+    fn glibc_version(&self) -> Option<(u32, u32)> {
+        let features = read(&self.header("features.h")?);
+        let define = |name: &str| {
+            features.lines().find_map(|line| {
+                let mut words = line.split_whitespace();
+                match (words.next(), words.next(), words.next()) {
+                    (Some("#define"), Some(n), Some(value)) if n == name => value.parse().ok(),
+                    _ => None,
+                }
+            })
+        };
+        Some((define("__GLIBC__")?, define("__GLIBC_MINOR__")?))
+    }
+}
+
+fn read(path: &Path) -> String {
+    fs::read_to_string(path).unwrap_or_default()
+}
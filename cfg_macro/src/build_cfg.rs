@@ -0,0 +1,100 @@
+/*!
+ * 构建脚本输出的自定义 cfg
+ *
+ * build.rs 在编译前探测构建环境（内核头文件、系统调用表、glibc 版本），
+ * 通过 `cargo:rustc-cfg` 输出 `has_io_uring`、`has_statx` 等标志。
+ * 它们和内置的 target_os 一样可以用在 #[cfg] 中：
+ *
+ * ```rust,ignore
+ * #[cfg(all(target_os = "linux", has_statx))]
+ * pub fn birth_time(path: &Path) -> io::Result<Option<SystemTime>> { ... }
+ * ```
+ *
+ * build.rs 同时用 `cargo:rustc-check-cfg` 声明了这些名字，拼错的 cfg（如 `has_statz`）会得到
+ * unexpected_cfgs 警告，而不是悄悄地永远为假。
+ */
+
+/// 构建脚本启用的 cfg 标志
+pub fn build_cfgs() -> &'static [&'static str] {
+    &[
+        #[cfg(has_io_uring)]
+        "has_io_uring",
+        #[cfg(has_statx)]
+        "has_statx",
+    ]
+}
+
+/// 构建时探测到的 glibc 版本，例如 "2.36"；不是 glibc 或者没有探测时为 None
+pub const GLIBC_VERSION: Option<&str> = option_env!("CFG_MACRO_GLIBC_VERSION");
+
+#[cfg(all(target_os = "linux", has_statx))]
+mod statx {
+    use std::ffi::{c_char, c_int, c_uint, CString};
+    use std::io;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+    use std::time::{Duration, SystemTime};
+
+    extern "C" {
+        fn statx(dirfd: c_int, path: *const c_char, flags: c_int, mask: c_uint, buf: *mut Statx) -> c_int;
+    }
+
+    const AT_FDCWD: c_int = -100;
+    const STATX_BTIME: c_uint = 0x800;
+
+    /// struct statx 的前半部分，之后的字段用填充补足内核要求的 256 字节
+    #[repr(C)]
+    #[allow(dead_code)] // 布局必须与内核一致，大部分字段不会读取
+    struct Statx {
+        mask: u32,
+        blksize: u32,
+        attributes: u64,
+        nlink: u32,
+        uid: u32,
+        gid: u32,
+        mode: u16,
+        spare0: u16,
+        ino: u64,
+        size: u64,
+        blocks: u64,
+        attributes_mask: u64,
+        atime: Timestamp,
+        btime: Timestamp,
+        rest: [u64; 20],
+    }
+
+    #[repr(C)]
+    #[allow(dead_code)]
+    struct Timestamp {
+        sec: i64,
+        nsec: u32,
+        reserved: i32,
+    }
+
+    /// 文件的创建时间（birth time）
+    ///
+    /// 传统的 stat 没有这个字段，statx 才能取到；文件系统不记录创建时间时返回 Ok(None)
+    pub fn birth_time(path: &Path) -> io::Result<Option<SystemTime>> {
+        let path = CString::new(path.as_os_str().as_bytes())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "path contains a NUL byte"))?;
+        let mut buf = std::mem::MaybeUninit::<Statx>::zeroed();
+        if unsafe { statx(AT_FDCWD, path.as_ptr(), 0, STATX_BTIME, buf.as_mut_ptr()) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let buf = unsafe { buf.assume_init() };
+        if buf.mask & STATX_BTIME == 0 {
+            return Ok(None);
+        }
+        // tv_nsec 总是非负的，负的 tv_sec 表示 1970 年之前
+        let seconds = Duration::from_secs(buf.btime.sec.unsigned_abs());
+        let time = if buf.btime.sec >= 0 {
+            SystemTime::UNIX_EPOCH + seconds
+        } else {
+            SystemTime::UNIX_EPOCH - seconds
+        };
+        Ok(Some(time + Duration::from_nanos(buf.btime.nsec.into())))
+    }
+}
+
+#[cfg(all(target_os = "linux", has_statx))]
+pub use statx::birth_time;
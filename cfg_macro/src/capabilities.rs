@@ -0,0 +1,559 @@
+/*!
+ * 第二部分：跨平台通用代码
+ *
+ * CrossPlatformCode 在 Linux、macOS 和 Windows 上都会编译，
+ * 它汇总了当前平台、架构和运行时探测到的能力（PlatformCapabilities）。
+ * Feature 枚举不做条件编译，在任何平台上都可以用来指代其他平台的功能。
+ */
+
+#[cfg(target_os = "linux")]
+use crate::linux_probe;
+use crate::TargetPlatform;
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+
+/**
+ * 跨平台通用代码示例
+ *
+ * 使用 any() 条件组合，这个结构体会在支持的任何平台上编译
+ * 这种方式适合实现平台无关的核心功能
+ *
+ * 支持的平台：Linux, macOS, Windows
+ * 不支持的平台：iOS, Android, WebAssembly 等
+ */
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+pub struct CrossPlatformCode {
+    /// 平台、架构等信息，默认为当前主机，测试中可以替换为模拟的平台
+    target: TargetPlatform,
+    /// 功能支持标志
+    capabilities: PlatformCapabilities,
+    /// 当前进程的 seccomp 模式；不是在 Linux 主机上探测得到的实例为 None
+    seccomp_mode: Option<SeccompMode>,
+}
+
+/// 平台能力枚举
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlatformCapabilities {
+    /// Linux 的各项能力都在运行时实际探测，见 linux_probe 模块
+    ///
+    /// 例外是 `signals`：信号是每个 Linux 内核都有的基本机制，没有可以探测的开关，固定为 true。
+    /// `seccomp` 表示内核支持 seccomp，进程当前是否处在过滤器之下见 [`CrossPlatformCode::seccomp_mode`]
+    Linux {
+        epoll: bool,
+        signals: bool,
+        unix_sockets: bool,
+        io_uring: bool,
+        inotify: bool,
+        seccomp: bool,
+        landlock: bool,
+        cgroup_v2: bool,
+    },
+    MacOS {
+        metal: bool,
+        gcd: bool,
+        core_foundation: bool
+    },
+    Windows {
+        win32: bool,
+        com: bool,
+        registry: bool
+    },
+}
+
+/**
+ * 平台功能
+ *
+ * 以前用字符串查询功能，拼错的名字和其他平台的功能都会悄悄得到 false。
+ * 现在每个功能都是一个枚举值，名字与能力报告中的键一致，
+ * 字符串通过 FromStr 解析，未知的名字会得到 FeatureError::Unknown。
+ *
+ * 枚举本身不做条件编译：在 Linux 上也能说出 Feature::Metal 是 macOS 的功能，
+ * 查询时返回 FeatureError::NotApplicable，而不是和"不支持"混为一谈。
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Feature {
+    // Linux
+    Epoll,
+    Signals,
+    UnixSockets,
+    IoUring,
+    Inotify,
+    Seccomp,
+    Landlock,
+    CgroupV2,
+    // macOS
+    Metal,
+    Gcd,
+    CoreFoundation,
+    // Windows
+    Win32,
+    Com,
+    Registry,
+}
+
+impl Feature {
+    /// 所有已知功能，同一平台的功能相邻，顺序与能力报告一致
+    pub const ALL: [Feature; 14] = [
+        Feature::Epoll,
+        Feature::Signals,
+        Feature::UnixSockets,
+        Feature::IoUring,
+        Feature::Inotify,
+        Feature::Seccomp,
+        Feature::Landlock,
+        Feature::CgroupV2,
+        Feature::Metal,
+        Feature::Gcd,
+        Feature::CoreFoundation,
+        Feature::Win32,
+        Feature::Com,
+        Feature::Registry,
+    ];
+
+    /// 功能名，例如 `"io_uring"`
+    pub fn name(self) -> &'static str {
+        match self {
+            Feature::Epoll => "epoll",
+            Feature::Signals => "signals",
+            Feature::UnixSockets => "unix_sockets",
+            Feature::IoUring => "io_uring",
+            Feature::Inotify => "inotify",
+            Feature::Seccomp => "seccomp",
+            Feature::Landlock => "landlock",
+            Feature::CgroupV2 => "cgroup_v2",
+            Feature::Metal => "metal",
+            Feature::Gcd => "gcd",
+            Feature::CoreFoundation => "core_foundation",
+            Feature::Win32 => "win32",
+            Feature::Com => "com",
+            Feature::Registry => "registry",
+        }
+    }
+
+    /// 功能所属的操作系统，取值与 `std::env::consts::OS` 相同
+    pub fn os(self) -> &'static str {
+        match self {
+            Feature::Epoll
+            | Feature::Signals
+            | Feature::UnixSockets
+            | Feature::IoUring
+            | Feature::Inotify
+            | Feature::Seccomp
+            | Feature::Landlock
+            | Feature::CgroupV2 => "linux",
+            Feature::Metal | Feature::Gcd | Feature::CoreFoundation => "macos",
+            Feature::Win32 | Feature::Com | Feature::Registry => "windows",
+        }
+    }
+
+    /// 某个操作系统上的全部功能
+    pub fn for_os(os: &str) -> impl Iterator<Item = Feature> + '_ {
+        Feature::ALL.into_iter().filter(move |feature| feature.os() == os)
+    }
+
+    /// 当前平台上的全部功能；不支持的平台上为空
+    pub fn current_platform() -> impl Iterator<Item = Feature> {
+        Feature::for_os(env::consts::OS)
+    }
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Feature {
+    type Err = FeatureError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Feature::ALL
+            .into_iter()
+            .find(|feature| feature.name() == s)
+            .ok_or_else(|| FeatureError::Unknown(s.to_string()))
+    }
+}
+
+/// 查询功能时无法给出"支持 / 不支持"的情况
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FeatureError {
+    /// 没有这个名字的功能，通常是拼写错误
+    Unknown(String),
+    /// 功能属于其他操作系统
+    NotApplicable { feature: Feature, os: &'static str },
+}
+
+impl fmt::Display for FeatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FeatureError::Unknown(name) => write!(f, "unknown feature `{}`", name),
+            FeatureError::NotApplicable { feature, os } => write!(
+                f,
+                "feature `{}` is only available on {}, not on {}",
+                feature,
+                feature.os(),
+                os
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FeatureError {}
+
+/// 进程的 seccomp 模式，对应 /proc/self/status 中 Seccomp 字段的 0、1、2
+///
+/// 与 Feature 一样不做条件编译，但只有 Linux 上才会探测到具体的值
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SeccompMode {
+    /// 没有任何限制
+    Disabled,
+    /// 严格模式：只允许 read、write、_exit 和 sigreturn
+    Strict,
+    /// 过滤模式：系统调用要经过 BPF 过滤器，Docker、systemd 和浏览器沙箱都使用这种模式
+    Filter,
+}
+
+impl SeccompMode {
+    /// 探测当前进程；不是 Linux 或者内核不支持 seccomp 时返回 None
+    pub fn detect() -> Option<Self> {
+        #[cfg(target_os = "linux")]
+        {
+            linux_probe::seccomp_mode()
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            None
+        }
+    }
+
+    /// 模式名，例如 `"filter"`
+    pub fn name(self) -> &'static str {
+        match self {
+            SeccompMode::Disabled => "disabled",
+            SeccompMode::Strict => "strict",
+            SeccompMode::Filter => "filter",
+        }
+    }
+
+    /// 系统调用是否受到限制
+    pub fn is_restricted(self) -> bool {
+        self != SeccompMode::Disabled
+    }
+}
+
+impl fmt::Display for SeccompMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+impl PlatformCapabilities {
+    /// 探测当前运行环境的能力
+    ///
+    /// 这里不能再用 `if cfg!(target_os = "linux") { ... }` 的写法：
+    /// cfg! 只是一个布尔常量，两个分支都必须在所有平台上编译通过，
+    /// 而 linux_probe 模块只在 Linux 上存在。因此每个平台各有一个
+    /// #[cfg] 标注的 detect 实现，编译时只会保留当前平台的那一个。
+    #[cfg(target_os = "linux")]
+    pub fn detect() -> Self {
+        PlatformCapabilities::Linux {
+            epoll: linux_probe::epoll(),
+            signals: true,
+            unix_sockets: linux_probe::unix_sockets(),
+            io_uring: linux_probe::io_uring(),
+            inotify: linux_probe::inotify(),
+            seccomp: linux_probe::seccomp_mode().is_some(),
+            landlock: linux_probe::landlock_abi().is_some(),
+            cgroup_v2: linux_probe::cgroup_v2(),
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub fn detect() -> Self {
+        PlatformCapabilities::MacOS {
+            metal: true,
+            gcd: true,
+            core_foundation: true,
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    pub fn detect() -> Self {
+        PlatformCapabilities::Windows {
+            win32: true,
+            com: true,
+            registry: true,
+        }
+    }
+
+    /// 某个平台名义上提供的能力：不做探测，全部视为可用
+    ///
+    /// 用于模拟的平台，也就是无法在当前主机上实际探测的平台；不支持的操作系统返回 None
+    pub fn nominal(os: &str) -> Option<Self> {
+        match os {
+            "linux" => Some(PlatformCapabilities::Linux {
+                epoll: true,
+                signals: true,
+                unix_sockets: true,
+                io_uring: true,
+                inotify: true,
+                seccomp: true,
+                landlock: true,
+                cgroup_v2: true,
+            }),
+            "macos" => Some(PlatformCapabilities::MacOS {
+                metal: true,
+                gcd: true,
+                core_foundation: true,
+            }),
+            "windows" => Some(PlatformCapabilities::Windows {
+                win32: true,
+                com: true,
+                registry: true,
+            }),
+            _ => None,
+        }
+    }
+
+    /// 给定平台的能力：当前主机实际探测，模拟的平台取名义上的能力
+    pub fn for_platform(target: &TargetPlatform) -> Option<Self> {
+        if target.is_host() {
+            Some(Self::detect())
+        } else {
+            Self::nominal(&target.os)
+        }
+    }
+
+    /// 平台种类，取值与 `std::env::consts::OS` 及 Feature::os() 相同
+    pub fn kind(&self) -> &'static str {
+        match self {
+            PlatformCapabilities::Linux { .. } => "linux",
+            PlatformCapabilities::MacOS { .. } => "macos",
+            PlatformCapabilities::Windows { .. } => "windows",
+        }
+    }
+
+    /// 查询单个功能；功能不属于这个平台时返回 None
+    pub fn get(&self, feature: Feature) -> Option<bool> {
+        let value = match (*self, feature) {
+            (PlatformCapabilities::Linux { epoll, .. }, Feature::Epoll) => epoll,
+            (PlatformCapabilities::Linux { signals, .. }, Feature::Signals) => signals,
+            (PlatformCapabilities::Linux { unix_sockets, .. }, Feature::UnixSockets) => unix_sockets,
+            (PlatformCapabilities::Linux { io_uring, .. }, Feature::IoUring) => io_uring,
+            (PlatformCapabilities::Linux { inotify, .. }, Feature::Inotify) => inotify,
+            (PlatformCapabilities::Linux { seccomp, .. }, Feature::Seccomp) => seccomp,
+            (PlatformCapabilities::Linux { landlock, .. }, Feature::Landlock) => landlock,
+            (PlatformCapabilities::Linux { cgroup_v2, .. }, Feature::CgroupV2) => cgroup_v2,
+            (PlatformCapabilities::MacOS { metal, .. }, Feature::Metal) => metal,
+            (PlatformCapabilities::MacOS { gcd, .. }, Feature::Gcd) => gcd,
+            (PlatformCapabilities::MacOS { core_foundation, .. }, Feature::CoreFoundation) => core_foundation,
+            (PlatformCapabilities::Windows { win32, .. }, Feature::Win32) => win32,
+            (PlatformCapabilities::Windows { com, .. }, Feature::Com) => com,
+            (PlatformCapabilities::Windows { registry, .. }, Feature::Registry) => registry,
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+impl CrossPlatformCode {
+    /// 创建跨平台代码实例
+    ///
+    /// 这个方法在所有支持的平台上都会编译
+    /// 通过运行时检测来提供平台特定的信息
+    pub fn new() -> Self {
+        // 根据平台探测不同的能力
+        Self {
+            target: TargetPlatform::host(),
+            capabilities: PlatformCapabilities::detect(),
+            seccomp_mode: SeccompMode::detect(),
+        }
+    }
+
+    /// 为给定平台创建实例，平台可以是模拟的
+    ///
+    /// 能力按 PlatformCapabilities::for_platform 的规则得到；
+    /// 不支持的操作系统（不是 Linux、macOS 或 Windows）返回 None
+    pub fn for_platform(target: &TargetPlatform) -> Option<Self> {
+        Some(Self {
+            target: target.clone(),
+            capabilities: PlatformCapabilities::for_platform(target)?,
+            seccomp_mode: if target.is_host() { SeccompMode::detect() } else { None },
+        })
+    }
+
+    /// 用给定的平台信息创建实例，不做任何探测
+    ///
+    /// 适合读取其他机器上生成的报告，或者在测试中构造特定的能力组合；
+    /// 指针宽度和系列家族按 TargetPlatform::simulated 的规则推断
+    pub fn with_capabilities(
+        platform: impl Into<String>,
+        architecture: impl Into<String>,
+        capabilities: PlatformCapabilities,
+    ) -> Self {
+        Self {
+            target: TargetPlatform::simulated(&platform.into(), &architecture.into()),
+            capabilities,
+            seccomp_mode: None,
+        }
+    }
+
+    /// 平台标识，与 `std::env::consts::OS` 相同，例如 "linux"
+    pub fn platform(&self) -> &str {
+        &self.target.os
+    }
+
+    /// 架构，与 `std::env::consts::ARCH` 相同，例如 "x86_64"
+    pub fn architecture(&self) -> &str {
+        &self.target.arch
+    }
+
+    /// 完整的平台描述
+    pub fn target(&self) -> &TargetPlatform {
+        &self.target
+    }
+
+    /// 探测到的全部能力
+    pub fn capabilities(&self) -> &PlatformCapabilities {
+        &self.capabilities
+    }
+
+    /// 当前进程的 seccomp 模式
+    ///
+    /// 能力表中的 `seccomp` 只说明内核支持 seccomp；容器运行时通常会给进程装上过滤器，
+    /// 这时为 `Some(SeccompMode::Filter)`。模拟的平台和读取的报告没有这项信息，返回 None
+    pub fn seccomp_mode(&self) -> Option<SeccompMode> {
+        self.seccomp_mode
+    }
+
+    /// 打印平台问候信息
+    pub fn greet(&self) {
+        print!("{}", self.greeting());
+    }
+
+    /// 平台问候信息，每行以换行结尾
+    pub fn greeting(&self) -> String {
+        let mut out = String::new();
+        self.write_greeting(&mut out).expect("写入 String 不会失败");
+        out
+    }
+
+    /// 把平台问候信息写入任意 fmt::Write
+    pub fn write_greeting<W: fmt::Write>(&self, out: &mut W) -> fmt::Result {
+        writeln!(out, "🌍 Hello from {} platform!", self.target.os)?;
+        writeln!(out, "🏗️  Architecture: {}", self.target.arch)?;
+
+        // 打印平台特定能力
+        match &self.capabilities {
+            PlatformCapabilities::Linux {
+                epoll,
+                signals,
+                unix_sockets,
+                io_uring,
+                inotify,
+                seccomp,
+                landlock,
+                cgroup_v2,
+            } => {
+                writeln!(out, "🐧 Linux Capabilities:")?;
+                writeln!(out, "   epoll support: {}", epoll)?;
+                writeln!(out, "   signal handling: {}", signals)?;
+                writeln!(out, "   Unix sockets: {}", unix_sockets)?;
+                writeln!(out, "   io_uring: {}", io_uring)?;
+                writeln!(out, "   inotify: {}", inotify)?;
+                match self.seccomp_mode {
+                    Some(mode) => writeln!(out, "   seccomp: {} (mode: {})", seccomp, mode)?,
+                    None => writeln!(out, "   seccomp: {}", seccomp)?,
+                }
+                writeln!(out, "   Landlock: {}", landlock)?;
+                writeln!(out, "   cgroup v2: {}", cgroup_v2)?;
+            },
+            PlatformCapabilities::MacOS { metal, gcd, core_foundation } => {
+                writeln!(out, "🍎 macOS Capabilities:")?;
+                writeln!(out, "   Metal API: {}", metal)?;
+                writeln!(out, "   GCD support: {}", gcd)?;
+                writeln!(out, "   Core Foundation: {}", core_foundation)?;
+            },
+            PlatformCapabilities::Windows { win32, com, registry } => {
+                writeln!(out, "🪟 Windows Capabilities:")?;
+                writeln!(out, "   Win32 API: {}", win32)?;
+                writeln!(out, "   COM support: {}", com)?;
+                writeln!(out, "   Registry access: {}", registry)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 检查特定功能是否支持
+    ///
+    /// 返回 Ok(true) / Ok(false) 表示支持 / 不支持；
+    /// 功能属于其他操作系统时返回 FeatureError::NotApplicable
+    pub fn supports(&self, feature: Feature) -> Result<bool, FeatureError> {
+        self.capabilities.get(feature).ok_or(FeatureError::NotApplicable {
+            feature,
+            os: self.capabilities.kind(),
+        })
+    }
+
+    /// 按名字检查功能，名字拼错时返回 FeatureError::Unknown
+    pub fn supports_feature(&self, feature: &str) -> Result<bool, FeatureError> {
+        self.supports(feature.parse()?)
+    }
+
+    /// 当前平台上的全部功能及其是否可用
+    pub fn features(&self) -> impl Iterator<Item = (Feature, bool)> + '_ {
+        Feature::for_os(self.capabilities.kind())
+            .filter_map(move |feature| Some((feature, self.capabilities.get(feature)?)))
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+impl Default for CrossPlatformCode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(all(test, any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simulated_macos_uses_the_macos_arms() {
+        let code = CrossPlatformCode::for_platform(&TargetPlatform::simulated("macos", "aarch64")).unwrap();
+        assert_eq!(code.capabilities().kind(), "macos");
+        assert!(code.greeting().contains("🍎 macOS Capabilities:\n   Metal API: true\n"));
+        assert_eq!(code.supports_feature("gcd"), Ok(true));
+        assert_eq!(
+            code.supports(Feature::Epoll),
+            Err(FeatureError::NotApplicable { feature: Feature::Epoll, os: "macos" })
+        );
+        assert_eq!(
+            code.features().map(|(f, _)| f).collect::<Vec<_>>(),
+            [Feature::Metal, Feature::Gcd, Feature::CoreFoundation]
+        );
+    }
+
+    #[test]
+    fn simulated_windows_uses_the_windows_arms() {
+        let target = TargetPlatform::simulated("windows", "x86");
+        let code = CrossPlatformCode::for_platform(&target).unwrap();
+        assert_eq!(code.target().pointer_width, 32);
+        assert!(code.greeting().starts_with("🌍 Hello from windows platform!\n🏗️  Architecture: x86\n🪟"));
+        assert_eq!(code.supports(Feature::Registry), Ok(true));
+        assert!(matches!(code.supports_feature("inotify"), Err(FeatureError::NotApplicable { .. })));
+    }
+
+    #[test]
+    fn only_linux_macos_and_windows_are_supported() {
+        assert!(CrossPlatformCode::for_platform(&TargetPlatform::simulated("freebsd", "x86_64")).is_none());
+        assert!(PlatformCapabilities::nominal("ios").is_none());
+        // 当前主机走实际探测
+        let host = CrossPlatformCode::for_platform(&TargetPlatform::host()).unwrap();
+        assert_eq!(host.capabilities(), CrossPlatformCode::new().capabilities());
+    }
+}
@@ -0,0 +1,592 @@
+/*!
+ * cfg 表达式的解析与求值
+ *
+ * 回答"这个条目在目标 X 上会不会被编译"，而不需要调用 rustc：
+ *
+ * ```rust
+ * use cfg_macro::{CfgExpr, CfgSet};
+ *
+ * // SpecificUnixCode 上的条件
+ * let expr: CfgExpr = r#"cfg(all(unix, not(target_os = "macos"),
+ *     any(target_arch = "x86_64", target_arch = "aarch64")))"#.parse().unwrap();
+ *
+ * assert!(expr.eval(&CfgSet::from_target_triple("x86_64-unknown-linux-gnu").unwrap()));
+ * assert!(!expr.eval(&CfgSet::from_target_triple("aarch64-apple-darwin").unwrap()));
+ * ```
+ *
+ * 目标用 CfgSet 描述，它是一组 cfg 名字（如 `unix`）和键值对（如 `target_os = "linux"`），
+ * 可以来自当前主机、目标三元组、TargetPlatform，或者 `rustc --print cfg` 的输出。
+ */
+
+use crate::TargetPlatform;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::str::FromStr;
+
+/// cfg 谓词
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CfgExpr {
+    /// 单独的名字，例如 `unix`、`debug_assertions`
+    Name(String),
+    /// 键值对，例如 `target_os = "linux"`
+    KeyValue(String, String),
+    /// `all(...)`，空列表为真
+    All(Vec<CfgExpr>),
+    /// `any(...)`，空列表为假
+    Any(Vec<CfgExpr>),
+    /// `not(...)`
+    Not(Box<CfgExpr>),
+}
+
+impl CfgExpr {
+    /// 解析 cfg 谓词，接受 `cfg(...)` 包裹的形式、`#[cfg(...)]` 属性或者单独的谓词
+    pub fn parse(input: &str) -> Result<Self, CfgParseError> {
+        let mut parser = Parser { src: input, pos: 0 };
+        let expr = parser.parse_top()?;
+        parser.skip_whitespace();
+        if parser.pos < input.len() {
+            return Err(parser.error("unexpected trailing input"));
+        }
+        Ok(expr)
+    }
+
+    /// 在给定目标上求值
+    pub fn eval(&self, cfg: &CfgSet) -> bool {
+        match self {
+            CfgExpr::Name(name) => cfg.contains_name(name),
+            CfgExpr::KeyValue(key, value) => cfg.contains(key, value),
+            CfgExpr::All(exprs) => exprs.iter().all(|expr| expr.eval(cfg)),
+            CfgExpr::Any(exprs) => exprs.iter().any(|expr| expr.eval(cfg)),
+            CfgExpr::Not(expr) => !expr.eval(cfg),
+        }
+    }
+}
+
+impl FromStr for CfgExpr {
+    type Err = CfgParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CfgExpr::parse(s)
+    }
+}
+
+/// 输出规范形式，例如 `all(unix, not(target_os = "macos"))`，可以再次解析
+impl fmt::Display for CfgExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |f: &mut fmt::Formatter<'_>, name: &str, exprs: &[CfgExpr]| {
+            write!(f, "{}(", name)?;
+            for (i, expr) in exprs.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(", ")?;
+                }
+                write!(f, "{}", expr)?;
+            }
+            f.write_str(")")
+        };
+        match self {
+            CfgExpr::Name(name) => f.write_str(name),
+            CfgExpr::KeyValue(key, value) => write!(f, "{} = {:?}", key, value),
+            CfgExpr::All(exprs) => list(f, "all", exprs),
+            CfgExpr::Any(exprs) => list(f, "any", exprs),
+            CfgExpr::Not(expr) => write!(f, "not({})", expr),
+        }
+    }
+}
+
+/// 解析 cfg 谓词失败的原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CfgParseError {
+    message: String,
+    /// 出错位置在输入中的字节偏移
+    offset: usize,
+}
+
+impl CfgParseError {
+    /// 出错位置在输入中的字节偏移
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl fmt::Display for CfgParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}", self.message, self.offset)
+    }
+}
+
+impl std::error::Error for CfgParseError {}
+
+/// 递归下降解析器
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: impl Into<String>) -> CfgParseError {
+        CfgParseError { message: message.into(), offset: self.pos }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.src[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.src[self.pos..].chars().next()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), CfgParseError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected `{}`", c)))
+        }
+    }
+
+    /// `#[cfg(...)]`、`cfg(...)` 或者单独的谓词
+    fn parse_top(&mut self) -> Result<CfgExpr, CfgParseError> {
+        let attribute = self.eat('#');
+        if attribute {
+            self.expect('[')?;
+        }
+        let start = self.pos;
+        let expr = if self.ident()? == "cfg" && self.peek() == Some('(') {
+            self.expect('(')?;
+            let expr = self.parse_predicate()?;
+            self.eat(',');
+            self.expect(')')?;
+            expr
+        } else {
+            // 不是 cfg(...)，回到开头按单独的谓词解析
+            self.pos = start;
+            self.parse_predicate()?
+        };
+        if attribute {
+            self.expect(']')?;
+        }
+        Ok(expr)
+    }
+
+    fn parse_predicate(&mut self) -> Result<CfgExpr, CfgParseError> {
+        let start = self.pos;
+        let name = self.ident()?;
+        if self.eat('=') {
+            let value = self.string()?;
+            return Ok(CfgExpr::KeyValue(name, value));
+        }
+        if self.peek() != Some('(') {
+            return Ok(CfgExpr::Name(name));
+        }
+        let args_start = self.pos;
+        let args = self.parse_list()?;
+        match name.as_str() {
+            "all" => Ok(CfgExpr::All(args)),
+            "any" => Ok(CfgExpr::Any(args)),
+            "not" => match <[CfgExpr; 1]>::try_from(args) {
+                Ok([arg]) => Ok(CfgExpr::Not(Box::new(arg))),
+                Err(_) => Err(CfgParseError {
+                    message: "`not` takes exactly one predicate".to_string(),
+                    offset: args_start,
+                }),
+            },
+            _ => Err(CfgParseError {
+                message: format!("unknown cfg operator `{}`; expected `all`, `any` or `not`", name),
+                offset: start,
+            }),
+        }
+    }
+
+    /// `(p, p, ...)`，允许末尾的逗号
+    fn parse_list(&mut self) -> Result<Vec<CfgExpr>, CfgParseError> {
+        self.expect('(')?;
+        let mut items = Vec::new();
+        while !self.eat(')') {
+            items.push(self.parse_predicate()?);
+            if !self.eat(',') {
+                self.expect(')')?;
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    fn ident(&mut self) -> Result<String, CfgParseError> {
+        self.skip_whitespace();
+        let rest = &self.src[self.pos..];
+        let len = rest
+            .char_indices()
+            .find(|&(i, c)| !(c == '_' || c.is_alphanumeric()) || (i == 0 && c.is_numeric()))
+            .map_or(rest.len(), |(i, _)| i);
+        if len == 0 {
+            return Err(self.error("expected a cfg name"));
+        }
+        self.pos += len;
+        Ok(rest[..len].to_string())
+    }
+
+    /// 带转义的字符串字面量，只支持 cfg 值中会出现的转义
+    fn string(&mut self) -> Result<String, CfgParseError> {
+        self.expect('"')?;
+        let mut out = String::new();
+        let mut chars = self.src[self.pos..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += i + 1;
+                    return Ok(out);
+                }
+                '\\' => match chars.next() {
+                    Some((_, '"')) => out.push('"'),
+                    Some((_, '\\')) => out.push('\\'),
+                    Some((_, 'n')) => out.push('\n'),
+                    Some((_, 't')) => out.push('\t'),
+                    _ => {
+                        self.pos += i;
+                        return Err(self.error("invalid escape in string literal"));
+                    }
+                },
+                c => out.push(c),
+            }
+        }
+        Err(self.error("unterminated string literal"))
+    }
+}
+
+/// 一个目标上成立的 cfg 名字和键值对
+///
+/// 同一个键可以有多个值，例如 wasm32-unknown-emscripten 的 `target_family` 同时是 "unix" 和 "wasm"。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CfgSet {
+    names: BTreeSet<String>,
+    values: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl CfgSet {
+    /// 空集合，所有名字和键值对都不成立
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 当前主机（也就是编译这个 crate 的目标）
+    ///
+    /// 值都来自编译期的 cfg!，因此与编译器看到的完全一致；
+    /// `feature` 不包含在内，需要时用 insert 加入
+    pub fn host() -> Self {
+        let mut cfg = CfgSet::from_platform(&TargetPlatform::host());
+        let endian = if cfg!(target_endian = "big") { "big" } else { "little" };
+        cfg.insert("target_endian", endian);
+
+        let env = [
+            (cfg!(target_env = "gnu"), "gnu"),
+            (cfg!(target_env = "musl"), "musl"),
+            (cfg!(target_env = "msvc"), "msvc"),
+            (cfg!(target_env = "sgx"), "sgx"),
+        ];
+        let env = env.iter().find(|(on, _)| *on).map_or("", |(_, name)| *name);
+        cfg.insert("target_env", env);
+
+        let vendor = [
+            (cfg!(target_vendor = "apple"), "apple"),
+            (cfg!(target_vendor = "pc"), "pc"),
+            (cfg!(target_vendor = "fortanix"), "fortanix"),
+        ];
+        let vendor = vendor.iter().find(|(on, _)| *on).map_or("unknown", |(_, name)| *name);
+        cfg.insert("target_vendor", vendor);
+
+        if cfg!(debug_assertions) {
+            cfg.insert_name("debug_assertions");
+        }
+        cfg
+    }
+
+    /// 由 TargetPlatform 得到 `target_os`、`target_arch`、`target_pointer_width`、
+    /// `target_family` 以及 `unix` / `windows`
+    pub fn from_platform(platform: &TargetPlatform) -> Self {
+        let mut cfg = CfgSet::new();
+        cfg.insert("target_os", &platform.os);
+        cfg.insert("target_arch", &platform.arch);
+        cfg.insert("target_pointer_width", &platform.pointer_width.to_string());
+        if !platform.family.is_empty() {
+            cfg.add_family(&platform.family);
+        }
+        cfg
+    }
+
+    /// 由目标三元组推断，例如 "x86_64-unknown-linux-gnu"、"aarch64-apple-darwin"
+    ///
+    /// 覆盖常见的键：`target_arch`、`target_os`、`target_family`、`target_env`、`target_vendor`、
+    /// `target_pointer_width`、`target_endian` 以及 `unix` / `windows`。
+    /// 无法识别架构时返回 None；需要完全准确的结果时请使用 `rustc --print cfg --target <triple>`
+    /// 的输出和 CfgSet::from_print_cfg
+    pub fn from_target_triple(triple: &str) -> Option<Self> {
+        let parts: Vec<&str> = triple.split('-').collect();
+        let arch = triple_arch(parts[0])?;
+        let rest = &parts[1..];
+
+        // 从后往前找，aarch64-linux-android 的操作系统是 android 而不是 linux
+        let os = rest.iter().rev().find_map(|part| triple_os(part)).unwrap_or("unknown");
+        // 第二段是厂商，除非它已经是操作系统（如 aarch64-linux-android）
+        let vendor = match rest {
+            [vendor, _, ..] if triple_os(vendor).is_none() => *vendor,
+            _ => "unknown",
+        };
+        let env = match rest.last() {
+            Some(last) if rest.len() >= 2 && triple_os(last).is_none() => triple_env(last),
+            _ => "",
+        };
+
+        let mut cfg = CfgSet::from_platform(&TargetPlatform::simulated(os, arch));
+        if arch.starts_with("wasm") {
+            cfg.add_family("wasm");
+            if os == "emscripten" {
+                cfg.add_family("unix");
+            }
+        }
+        // powerpc64le、mipsel 等带后缀的变体是小端，其余按架构的默认字节序
+        let raw_arch = parts[0];
+        let big_endian = raw_arch.ends_with("be")
+            || (matches!(arch, "powerpc" | "powerpc64" | "mips" | "mips64" | "s390x" | "sparc" | "sparc64" | "m68k")
+                && !raw_arch.ends_with("le")
+                && !raw_arch.ends_with("el"));
+        cfg.insert("target_endian", if big_endian { "big" } else { "little" });
+        cfg.insert("target_vendor", vendor);
+        cfg.insert("target_env", env);
+        Some(cfg)
+    }
+
+    /// 解析 `rustc --print cfg` 的输出：每行一个名字或者 `key="value"`
+    pub fn from_print_cfg(output: &str) -> Result<Self, CfgParseError> {
+        let mut cfg = CfgSet::new();
+        let mut offset = 0;
+        for line in output.split_inclusive('\n') {
+            let trimmed = line.trim();
+            if !trimmed.is_empty() {
+                let at = |err: CfgParseError| CfgParseError { offset: offset + err.offset, ..err };
+                match CfgExpr::parse(trimmed).map_err(at)? {
+                    CfgExpr::Name(name) => cfg.insert_name(&name),
+                    CfgExpr::KeyValue(key, value) => cfg.insert(&key, &value),
+                    _ => {
+                        return Err(CfgParseError {
+                            message: format!("expected a name or `key=\"value\"`, found `{}`", trimmed),
+                            offset,
+                        })
+                    }
+                }
+            }
+            offset += line.len();
+        }
+        Ok(cfg)
+    }
+
+    /// 加入一个名字，例如 `unix`
+    pub fn insert_name(&mut self, name: &str) {
+        self.names.insert(name.to_string());
+    }
+
+    /// 加入一个键值对，例如 `("feature", "std")`
+    pub fn insert(&mut self, key: &str, value: &str) {
+        self.values.entry(key.to_string()).or_default().insert(value.to_string());
+    }
+
+    pub fn contains_name(&self, name: &str) -> bool {
+        self.names.contains(name)
+    }
+
+    pub fn contains(&self, key: &str, value: &str) -> bool {
+        self.values.get(key).is_some_and(|values| values.contains(value))
+    }
+
+    /// 某个键的全部值
+    pub fn values(&self, key: &str) -> impl Iterator<Item = &str> {
+        self.values.get(key).into_iter().flatten().map(String::as_str)
+    }
+
+    /// `target_family = "unix"` 同时意味着名字 `unix` 成立，windows 同理
+    fn add_family(&mut self, family: &str) {
+        self.insert("target_family", family);
+        if family == "unix" || family == "windows" {
+            self.insert_name(family);
+        }
+    }
+}
+
+/// 三元组第一段对应的 `target_arch`
+fn triple_arch(arch: &str) -> Option<&'static str> {
+    let arch = match arch {
+        "x86_64" | "x86_64h" => "x86_64",
+        "i386" | "i586" | "i686" => "x86",
+        "aarch64" | "aarch64_be" | "arm64" | "arm64e" => "aarch64",
+        "powerpc" => "powerpc",
+        "powerpc64" | "powerpc64le" => "powerpc64",
+        "mips" | "mipsel" => "mips",
+        "mips64" | "mips64el" => "mips64",
+        "s390x" => "s390x",
+        "sparc" => "sparc",
+        "sparc64" | "sparcv9" => "sparc64",
+        "loongarch64" => "loongarch64",
+        "wasm32" => "wasm32",
+        "wasm64" => "wasm64",
+        "m68k" => "m68k",
+        "avr" => "avr",
+        "msp430" => "msp430",
+        "hexagon" => "hexagon",
+        "csky" => "csky",
+        a if a.starts_with("riscv64") => "riscv64",
+        a if a.starts_with("riscv32") => "riscv32",
+        a if a.starts_with("arm") || a.starts_with("thumb") => "arm",
+        _ => return None,
+    };
+    Some(arch)
+}
+
+/// 三元组中表示操作系统的段对应的 `target_os`
+fn triple_os(part: &str) -> Option<&'static str> {
+    let os = match part {
+        "linux" => "linux",
+        "darwin" | "macos" => "macos",
+        "ios" => "ios",
+        "tvos" => "tvos",
+        "watchos" => "watchos",
+        "visionos" => "visionos",
+        "windows" => "windows",
+        "freebsd" => "freebsd",
+        "netbsd" => "netbsd",
+        "openbsd" => "openbsd",
+        "dragonfly" => "dragonfly",
+        "solaris" => "solaris",
+        "illumos" => "illumos",
+        "fuchsia" => "fuchsia",
+        "redox" => "redox",
+        "haiku" => "haiku",
+        "emscripten" => "emscripten",
+        "uefi" => "uefi",
+        "none" => "none",
+        p if p.starts_with("android") => "android",
+        p if p.starts_with("wasi") => "wasi",
+        _ => return None,
+    };
+    Some(os)
+}
+
+/// 三元组最后一段对应的 `target_env`
+fn triple_env(part: &str) -> &'static str {
+    if part.starts_with("gnu") {
+        "gnu"
+    } else if part.starts_with("musl") {
+        "musl"
+    } else if part.starts_with("msvc") {
+        "msvc"
+    } else if part == "sgx" {
+        "sgx"
+    } else {
+        ""
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPECIFIC_UNIX: &str =
+        r#"all(unix, not(target_os = "macos"), any(target_arch = "x86_64", target_arch = "aarch64"))"#;
+
+    fn triple(triple: &str) -> CfgSet {
+        CfgSet::from_target_triple(triple).unwrap()
+    }
+
+    #[test]
+    fn parses_every_accepted_form_to_the_same_expression() {
+        let bare = CfgExpr::parse(SPECIFIC_UNIX).unwrap();
+        assert_eq!(CfgExpr::parse(&format!("cfg({})", SPECIFIC_UNIX)).unwrap(), bare);
+        assert_eq!(CfgExpr::parse(&format!("#[cfg({})]", SPECIFIC_UNIX)).unwrap(), bare);
+        // 规范形式可以再次解析
+        assert_eq!(bare.to_string(), SPECIFIC_UNIX);
+        assert_eq!(CfgExpr::parse(&bare.to_string()).unwrap(), bare);
+        // `cfg` 本身也可以是一个名字
+        assert_eq!(CfgExpr::parse("cfg").unwrap(), CfgExpr::Name("cfg".to_string()));
+        assert_eq!(CfgExpr::parse("all(unix,)").unwrap(), CfgExpr::All(vec![CfgExpr::Name("unix".into())]));
+    }
+
+    #[test]
+    fn reports_errors_with_offsets() {
+        let err = CfgExpr::parse("not(unix, windows)").unwrap_err();
+        assert_eq!(err.to_string(), "`not` takes exactly one predicate at offset 3");
+        let err = CfgExpr::parse("both(unix)").unwrap_err();
+        assert_eq!(err.offset(), 0);
+        let err = CfgExpr::parse(r#"target_os = "linux"#).unwrap_err();
+        assert_eq!(err.to_string(), "unterminated string literal at offset 13");
+        assert!(CfgExpr::parse("unix windows").is_err());
+        assert!(CfgExpr::parse("target_os = linux").is_err());
+    }
+
+    #[test]
+    fn evaluates_specific_unix_against_triples() {
+        let expr = CfgExpr::parse(SPECIFIC_UNIX).unwrap();
+        assert!(expr.eval(&triple("x86_64-unknown-linux-gnu")));
+        assert!(expr.eval(&triple("aarch64-linux-android")));
+        assert!(expr.eval(&triple("x86_64-unknown-freebsd")));
+        assert!(!expr.eval(&triple("aarch64-apple-darwin")));
+        assert!(!expr.eval(&triple("x86_64-pc-windows-msvc")));
+        assert!(!expr.eval(&triple("armv7-unknown-linux-gnueabihf")));
+    }
+
+    #[test]
+    fn infers_common_keys_from_triples() {
+        let linux = triple("armv7-unknown-linux-musleabihf");
+        assert!(linux.contains("target_arch", "arm") && linux.contains("target_env", "musl"));
+        assert!(linux.contains("target_pointer_width", "32") && linux.contains_name("unix"));
+
+        let mac = triple("aarch64-apple-darwin");
+        assert!(mac.contains("target_os", "macos") && mac.contains("target_vendor", "apple"));
+        assert!(mac.contains("target_env", ""));
+
+        let android = triple("aarch64-linux-android");
+        assert!(android.contains("target_os", "android") && android.contains("target_vendor", "unknown"));
+
+        let wasm = triple("wasm32-unknown-unknown");
+        assert_eq!(wasm.values("target_family").collect::<Vec<_>>(), ["wasm"]);
+        assert!(!wasm.contains_name("unix") && wasm.contains("target_os", "unknown"));
+        let emscripten = triple("wasm32-unknown-emscripten");
+        assert_eq!(emscripten.values("target_family").collect::<Vec<_>>(), ["unix", "wasm"]);
+
+        assert!(triple("powerpc64-unknown-linux-gnu").contains("target_endian", "big"));
+        assert!(triple("powerpc64le-unknown-linux-gnu").contains("target_endian", "little"));
+        assert!(triple("mipsel-unknown-linux-gnu").contains("target_endian", "little"));
+        assert!(CfgSet::from_target_triple("z80-unknown-none").is_none());
+    }
+
+    #[test]
+    fn host_agrees_with_the_compiler() {
+        let host = CfgSet::host();
+        let expr = CfgExpr::parse(SPECIFIC_UNIX).unwrap();
+        assert_eq!(
+            expr.eval(&host),
+            cfg!(all(unix, not(target_os = "macos"), any(target_arch = "x86_64", target_arch = "aarch64")))
+        );
+        assert_eq!(CfgExpr::parse("unix").unwrap().eval(&host), cfg!(unix));
+        assert_eq!(CfgExpr::parse(r#"target_env = "gnu""#).unwrap().eval(&host), cfg!(target_env = "gnu"));
+        assert_eq!(CfgExpr::parse("debug_assertions").unwrap().eval(&host), cfg!(debug_assertions));
+    }
+
+    #[test]
+    fn parses_rustc_print_cfg_output() {
+        let output = "debug_assertions\ntarget_arch=\"x86_64\"\ntarget_feature=\"sse2\"\ntarget_feature=\"fxsr\"\nunix\n";
+        let cfg = CfgSet::from_print_cfg(output).unwrap();
+        assert!(cfg.contains_name("unix") && cfg.contains("target_feature", "fxsr"));
+        assert!(CfgExpr::parse(r#"all(unix, target_feature = "sse2")"#).unwrap().eval(&cfg));
+
+        let err = CfgSet::from_print_cfg("unix\nall(unix)\n").unwrap_err();
+        assert_eq!(err.offset(), 5);
+    }
+}
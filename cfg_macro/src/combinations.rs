@@ -0,0 +1,413 @@
+/*!
+ * 第五部分：复杂条件组合
+ *
+ * 以下类型展示了更复杂的条件组合用法，每个类型只在满足条件的目标上存在：
+ *
+ * | 类型 | 条件 |
+ * |------|------|
+ * | Linux64Code | `all(target_os = "linux", target_pointer_width = "64")` |
+ * | ApplePlatformCode | `any(target_os = "macos", target_os = "ios")` |
+ * | UnixLikeCode | `not(target_os = "windows")` |
+ * | SpecificUnixCode | `all(unix, not(target_os = "macos"), any(target_arch = "x86_64", target_arch = "aarch64"))` |
+ *
+ * 使用这些类型的代码也要加上相同的条件，否则在其他目标上会编译失败。
+ */
+
+#[cfg(not(target_os = "windows"))]
+use crate::environment::RuntimeEnvironment;
+#[cfg(not(target_os = "windows"))]
+use crate::posix::PosixReport;
+#[cfg(all(
+    unix,
+    not(target_os = "macos"),
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+use crate::simd::SimdFeatures;
+
+#[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+use crate::linux_probe;
+#[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+use std::{env, fmt};
+
+// 只有在 Linux 系统且为 64 位架构时才编译
+#[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+pub struct Linux64Code {
+    processor_count: usize,
+    memory_size: u64,
+}
+
+/// 决定线程数的来源
+#[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThreadCountSource {
+    /// 环境变量 CFG_MACRO_THREADS
+    EnvOverride,
+    /// cgroup v2 的 cpu.max
+    CgroupV2Quota,
+    /// cgroup v1 的 cpu.cfs_quota_us
+    CgroupV1Quota,
+    /// CPU 亲和性掩码
+    Affinity,
+    /// 达到了线程数上限 Linux64Code::MAX_THREADS
+    Limit,
+    /// 以上信息都拿不到时，退回标准库的 available_parallelism
+    Fallback,
+}
+
+#[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+impl fmt::Display for ThreadCountSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ThreadCountSource::EnvOverride => "CFG_MACRO_THREADS",
+            ThreadCountSource::CgroupV2Quota => "cgroup v2 cpu.max",
+            ThreadCountSource::CgroupV1Quota => "cgroup v1 cpu.cfs_quota_us",
+            ThreadCountSource::Affinity => "CPU affinity",
+            ThreadCountSource::Limit => "thread limit",
+            ThreadCountSource::Fallback => "available_parallelism",
+        })
+    }
+}
+
+/// 推荐的线程数及其来源
+#[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThreadCount {
+    pub threads: usize,
+    pub source: ThreadCountSource,
+}
+
+// Linux 64位专用的实现
+#[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+impl Linux64Code {
+    /// 自动推算时的线程数上限；环境变量指定的值不受此限制
+    pub const MAX_THREADS: usize = 16;
+
+    /// 覆盖自动推算结果的环境变量，值必须是正整数
+    pub const THREADS_ENV: &'static str = "CFG_MACRO_THREADS";
+
+    pub fn new() -> Self {
+        Self {
+            // 进程实际能用的 CPU，而不是宿主机上的全部 CPU
+            processor_count: linux_probe::cpu_affinity_count()
+                .or_else(|| std::thread::available_parallelism().ok().map(|n| n.get()))
+                .unwrap_or(1),
+            memory_size: linux_probe::mem_total_bytes().unwrap_or(0),
+        }
+    }
+
+    /// 进程可用的 CPU 数量（亲和性掩码中的 CPU），不考虑 cgroup 配额
+    pub fn processor_count(&self) -> usize {
+        self.processor_count
+    }
+
+    /// 物理内存总量（字节），读取 /proc/meminfo 失败时为 0
+    pub fn memory_size(&self) -> u64 {
+        self.memory_size
+    }
+
+    pub fn get_optimal_thread_count(&self) -> usize {
+        self.thread_count().threads
+    }
+
+    /// 推算线程数，并说明是哪一项限制决定了结果
+    ///
+    /// 依次考虑：
+    /// 1. 环境变量 CFG_MACRO_THREADS，设置了就直接使用；
+    /// 2. CPU 亲和性掩码中的 CPU 数量；
+    /// 3. cgroup 的 CPU 配额（v2 优先，其次 v1），例如 `docker --cpus=2`；
+    /// 4. 上限 MAX_THREADS。
+    ///
+    /// 2 到 4 中最小的那一项决定结果。容器里只看 CPU 数量会看到宿主机的全部 CPU，
+    /// 按它开线程会远远超出配额，导致频繁的 CFS 限流。
+    pub fn thread_count(&self) -> ThreadCount {
+        if let Some(threads) = env::var(Self::THREADS_ENV).ok().and_then(|value| Self::parse_threads_env(&value)) {
+            return ThreadCount { threads, source: ThreadCountSource::EnvOverride };
+        }
+
+        let v2 = linux_probe::cgroup_v2_cpu_limit();
+        // v2 的配额优先，只有没有时才去读 v1 的文件
+        let v1 = if v2.is_none() { linux_probe::cgroup_v1_cpu_limit() } else { None };
+        Self::choose_thread_count(linux_probe::cpu_affinity_count(), v2, v1, self.processor_count)
+    }
+
+    /// 环境变量的值：正整数，允许首尾空白；其他值都被忽略
+    fn parse_threads_env(value: &str) -> Option<usize> {
+        value.trim().parse::<usize>().ok().filter(|&threads| threads > 0)
+    }
+
+    /// 在探测结果中取最小的一项；数量相同时按亲和性、cgroup 配额、上限的顺序归因
+    ///
+    /// 与 /proc、/sys 无关，方便单独测试各种组合
+    fn choose_thread_count(
+        affinity: Option<usize>,
+        v2: Option<usize>,
+        v1: Option<usize>,
+        processor_count: usize,
+    ) -> ThreadCount {
+        let mut best = match affinity {
+            Some(threads) => ThreadCount { threads, source: ThreadCountSource::Affinity },
+            None => ThreadCount { threads: processor_count, source: ThreadCountSource::Fallback },
+        };
+        let quota = v2
+            .map(|threads| ThreadCount { threads, source: ThreadCountSource::CgroupV2Quota })
+            .or_else(|| v1.map(|threads| ThreadCount { threads, source: ThreadCountSource::CgroupV1Quota }));
+        let limit = ThreadCount { threads: Self::MAX_THREADS, source: ThreadCountSource::Limit };
+        for candidate in quota.into_iter().chain([limit]) {
+            if candidate.threads < best.threads {
+                best = candidate;
+            }
+        }
+        best
+    }
+}
+
+#[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+impl Default for Linux64Code {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// 所有苹果平台（macOS, iOS）都编译的扩展结构体
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+pub struct ApplePlatformCode {
+    platform_type: String,
+    metal_available: bool,
+    core_foundation_version: u32,
+}
+
+// 苹果平台实现
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+impl ApplePlatformCode {
+    pub fn new() -> Self {
+        Self {
+            platform_type: std::env::consts::OS.to_string(),
+            metal_available: cfg!(target_arch = "aarch64"), // Apple Silicon 默认支持 Metal
+            core_foundation_version: 1500, // 示例版本号
+        }
+    }
+
+    pub fn supports_metal(&self) -> bool {
+        self.metal_available
+    }
+
+    /// "macos" 或 "ios"
+    pub fn platform_type(&self) -> &str {
+        &self.platform_type
+    }
+
+    pub fn core_foundation_version(&self) -> u32 {
+        self.core_foundation_version
+    }
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+impl Default for ApplePlatformCode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// 非 Windows 平台编译（Unix-like 系统）的增强版
+#[cfg(not(target_os = "windows"))]
+pub struct UnixLikeCode {
+    unix_features: Vec<String>,
+    posix: PosixReport,
+    environment: RuntimeEnvironment,
+}
+
+// Unix-like 系统实现
+#[cfg(not(target_os = "windows"))]
+impl UnixLikeCode {
+    /// 列出系统特性，查询 POSIX 版本与系统限制，并检测容器与虚拟化环境
+    pub fn new() -> Self {
+        Self::with_environment(RuntimeEnvironment::detect())
+    }
+
+    /// 使用给定的运行环境，例如在测试中模拟容器
+    pub fn with_environment(environment: RuntimeEnvironment) -> Self {
+        let mut features = vec![
+            "POSIX 兼容".to_string(),
+            "Unix 信号处理".to_string(),
+            "文件描述符".to_string(),
+        ];
+
+        // 根据具体系统添加特性
+        if cfg!(target_os = "linux") {
+            features.push("inotify 文件监控".to_string());
+            features.push("epoll I/O 多路复用".to_string());
+        } else if cfg!(target_os = "macos") {
+            features.push("FSEvents 文件系统事件".to_string());
+            features.push("kqueue 事件通知".to_string());
+        }
+
+        Self {
+            unix_features: features,
+            posix: PosixReport::detect(),
+            environment,
+        }
+    }
+
+    pub fn get_features(&self) -> &Vec<String> {
+        &self.unix_features
+    }
+
+    /// 系统是否声明了 POSIX 版本（`sysconf(_SC_VERSION)`）
+    pub fn is_posix_compliant(&self) -> bool {
+        self.posix.version.is_some()
+    }
+
+    /// POSIX 版本、sysconf / pathconf 限制和当前进程的资源限制
+    pub fn posix(&self) -> &PosixReport {
+        &self.posix
+    }
+
+    /// 运行环境：容器、虚拟机、WSL
+    pub fn environment(&self) -> &RuntimeEnvironment {
+        &self.environment
+    }
+
+    /// 是否运行在容器中；容器里的 CPU、内存和文件系统通常受限，很多默认值需要调整
+    pub fn in_container(&self) -> bool {
+        self.environment.is_containerized()
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+impl Default for UnixLikeCode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// 复杂的多重条件组合示例
+// Unix 系统 + 非 macOS + (x86_64 或 aarch64 架构)
+#[cfg(all(
+    unix,
+    not(target_os = "macos"),
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+pub struct SpecificUnixCode {
+    variant: String,
+    simd: SimdFeatures,
+    supported_features: Vec<String>,
+}
+
+// 特定 Unix 系统实现
+#[cfg(all(
+    unix,
+    not(target_os = "macos"),
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+impl SpecificUnixCode {
+    /// 按当前 CPU 检测到的 SIMD 指令集创建
+    pub fn new() -> Self {
+        Self::with_simd(SimdFeatures::detect())
+    }
+
+    /// 使用给定的 SIMD 指令集，例如模拟一台没有 AVX2 的机器
+    pub fn with_simd(simd: SimdFeatures) -> Self {
+        let variant = if cfg!(target_os = "linux") {
+            "Linux".to_string()
+        } else if cfg!(target_os = "freebsd") {
+            "FreeBSD".to_string()
+        } else {
+            "Unknown Unix".to_string()
+        };
+
+        let mut features = vec![
+            "POSIX 标准".to_string(),
+            "64位架构支持".to_string(),
+        ];
+
+        if cfg!(target_os = "linux") {
+            features.push("Linux 特定优化".to_string());
+        }
+        features.extend(simd.iter().map(|feature| format!("SIMD: {}", feature)));
+
+        Self {
+            variant,
+            simd,
+            supported_features: features,
+        }
+    }
+
+    pub fn get_variant(&self) -> &str {
+        &self.variant
+    }
+
+    /// 由 SIMD 指令集推导的优化级别：只有标量代码时为 1，128 位（SSE4.2、NEON）为 2，
+    /// AVX2 为 3，AVX-512 为 4
+    pub fn optimization_level(&self) -> u8 {
+        self.simd.optimization_level()
+    }
+
+    pub fn simd_features(&self) -> SimdFeatures {
+        self.simd
+    }
+
+    pub fn supported_features(&self) -> &[String] {
+        &self.supported_features
+    }
+}
+
+#[cfg(all(
+    unix,
+    not(target_os = "macos"),
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+impl Default for SpecificUnixCode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(all(test, target_os = "linux", target_pointer_width = "64"))]
+mod tests {
+    use super::*;
+
+    fn choose(
+        affinity: Option<usize>,
+        v2: Option<usize>,
+        v1: Option<usize>,
+        processor_count: usize,
+    ) -> (usize, ThreadCountSource) {
+        let count = Linux64Code::choose_thread_count(affinity, v2, v1, processor_count);
+        (count.threads, count.source)
+    }
+
+    #[test]
+    fn the_smallest_limit_decides_the_thread_count() {
+        assert_eq!(choose(Some(4), None, None, 64), (4, ThreadCountSource::Affinity));
+        assert_eq!(choose(None, None, None, 3), (3, ThreadCountSource::Fallback));
+        assert_eq!(choose(Some(8), Some(2), None, 64), (2, ThreadCountSource::CgroupV2Quota));
+        assert_eq!(choose(Some(8), None, Some(3), 64), (3, ThreadCountSource::CgroupV1Quota));
+        assert_eq!(choose(None, Some(6), None, 12), (6, ThreadCountSource::CgroupV2Quota));
+        assert_eq!(choose(Some(64), Some(32), None, 64), (Linux64Code::MAX_THREADS, ThreadCountSource::Limit));
+    }
+
+    #[test]
+    fn cgroup_v2_takes_precedence_over_v1() {
+        // 同时能读到两种配额时只看 v2，即使 v1 的更小
+        assert_eq!(choose(Some(8), Some(4), Some(1), 64), (4, ThreadCountSource::CgroupV2Quota));
+        // v2 的配额比其他几项都大时，也不会退回去用 v1 的配额
+        assert_eq!(choose(Some(8), Some(32), Some(2), 64), (8, ThreadCountSource::Affinity));
+    }
+
+    #[test]
+    fn only_positive_integers_override_the_thread_count() {
+        assert_eq!(Linux64Code::parse_threads_env("64"), Some(64));
+        assert_eq!(Linux64Code::parse_threads_env(" 3\n"), Some(3));
+        assert_eq!(Linux64Code::parse_threads_env("0"), None);
+        assert_eq!(Linux64Code::parse_threads_env("-2"), None);
+        assert_eq!(Linux64Code::parse_threads_env("four"), None);
+    }
+
+    #[test]
+    fn ties_are_attributed_to_the_earlier_source() {
+        assert_eq!(choose(Some(2), Some(2), None, 64), (2, ThreadCountSource::Affinity));
+        assert_eq!(choose(None, Some(3), None, 3), (3, ThreadCountSource::Fallback));
+        assert_eq!(choose(Some(32), Some(16), None, 64), (16, ThreadCountSource::CgroupV2Quota));
+        assert_eq!(choose(Some(16), None, None, 64), (16, ThreadCountSource::Affinity));
+    }
+}
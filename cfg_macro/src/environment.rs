@@ -0,0 +1,327 @@
+/*!
+ * 容器与虚拟化环境检测
+ *
+ * 同一个 Linux 二进制文件可能直接跑在物理机上，也可能跑在 Docker 容器、Kubernetes Pod、
+ * systemd-nspawn 容器、WSL 或者虚拟机里。这些信息在编译期无从得知，只能在运行时
+ * 从各种痕迹中推断：
+ *
+ * | 环境 | 依据 |
+ * |------|------|
+ * | Kubernetes | 环境变量 `KUBERNETES_SERVICE_HOST`、服务账户目录、`/proc/1/cgroup` 中的 `kubepods` |
+ * | Docker | `/.dockerenv`、`/proc/1/cgroup` 中的 `docker` |
+ * | Podman | `/run/.containerenv`、`/proc/1/cgroup` 中的 `libpod` |
+ * | systemd-nspawn、LXC 等 | `/run/systemd/container`（由容器管理器写入） |
+ * | WSL | `/proc/sys/kernel/osrelease` 中的 `microsoft`、环境变量 `WSL_DISTRO_NAME` |
+ * | 虚拟机 | DMI 信息（`/sys/class/dmi/id`）、`/sys/hypervisor/type`、CPU 的 hypervisor 标志 |
+ *
+ * 容器和虚拟机可以同时存在，例如云主机上的 Docker 容器。非 Linux 系统上不做检测，
+ * 结果总是"物理机、非容器"。
+ */
+
+use std::fmt;
+
+/// 容器运行时
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Container {
+    Docker,
+    Podman,
+    /// Kubernetes Pod，底层运行时可能是 containerd、CRI-O 或 Docker
+    Kubernetes,
+    SystemdNspawn,
+    Lxc,
+    /// 其他容器管理器，保存 `/run/systemd/container` 中的名字
+    Other(String),
+}
+
+impl fmt::Display for Container {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Container::Docker => "docker",
+            Container::Podman => "podman",
+            Container::Kubernetes => "kubernetes",
+            Container::SystemdNspawn => "systemd-nspawn",
+            Container::Lxc => "lxc",
+            Container::Other(name) => name,
+        })
+    }
+}
+
+/// 虚拟机监控程序
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Virtualization {
+    Kvm,
+    Qemu,
+    VMware,
+    VirtualBox,
+    HyperV,
+    Xen,
+    /// DMI 中能识别出厂商，但不在以上列表中，保存厂商名
+    Other(String),
+    /// 只知道运行在虚拟机中（CPU 报告了 hypervisor 标志），不知道是哪一种
+    Unknown,
+}
+
+impl fmt::Display for Virtualization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Virtualization::Kvm => "kvm",
+            Virtualization::Qemu => "qemu",
+            Virtualization::VMware => "vmware",
+            Virtualization::VirtualBox => "virtualbox",
+            Virtualization::HyperV => "hyper-v",
+            Virtualization::Xen => "xen",
+            Virtualization::Other(vendor) => vendor,
+            Virtualization::Unknown => "unknown",
+        })
+    }
+}
+
+/// 进程所在的运行环境
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct RuntimeEnvironment {
+    /// 所在的容器；不在容器中时为 None
+    pub container: Option<Container>,
+    /// 所在的虚拟机；运行在物理机上时为 None
+    pub virtualization: Option<Virtualization>,
+    /// 是否运行在 WSL（Windows Subsystem for Linux）中
+    pub wsl: bool,
+}
+
+impl RuntimeEnvironment {
+    /// 检测当前进程的运行环境
+    pub fn detect() -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            Evidence::collect().classify()
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            Self::default()
+        }
+    }
+
+    pub fn is_containerized(&self) -> bool {
+        self.container.is_some()
+    }
+
+    pub fn is_virtualized(&self) -> bool {
+        self.virtualization.is_some()
+    }
+
+    /// 运行在物理机上，且不在容器和 WSL 中
+    pub fn is_bare_metal(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl fmt::Display for RuntimeEnvironment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(container) = &self.container {
+            parts.push(format!("container={}", container));
+        }
+        if self.wsl {
+            parts.push("wsl".to_string());
+        }
+        if let Some(virtualization) = &self.virtualization {
+            parts.push(format!("vm={}", virtualization));
+        }
+        if parts.is_empty() {
+            f.write_str("bare metal")
+        } else {
+            f.write_str(&parts.join(", "))
+        }
+    }
+}
+
+/// 检测所需的原始信息，与判断逻辑分开，方便用固定的输入测试
+#[derive(Debug, Default)]
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))] // 只有 Linux 上会收集
+struct Evidence {
+    /// /proc/1/cgroup 的内容
+    pid1_cgroup: String,
+    /// /.dockerenv 是否存在
+    dockerenv: bool,
+    /// /run/.containerenv 是否存在
+    containerenv: bool,
+    /// /run/systemd/container 的内容
+    systemd_container: String,
+    /// 是否有 Kubernetes 注入的环境变量或服务账户目录
+    kubernetes: bool,
+    /// /proc/sys/kernel/osrelease 的内容
+    osrelease: String,
+    /// 是否设置了 WSL 注入的环境变量
+    wsl_env: bool,
+    /// /sys/class/dmi/id 下的 sys_vendor 与 product_name
+    dmi_vendor: String,
+    dmi_product: String,
+    /// /sys/hypervisor/type 的内容（Xen 客户机）
+    hypervisor_type: String,
+    /// /proc/cpuinfo 的 flags 中是否有 hypervisor
+    hypervisor_flag: bool,
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+impl Evidence {
+    #[cfg(target_os = "linux")]
+    fn collect() -> Self {
+        use std::env;
+        use std::fs;
+        use std::path::Path;
+
+        let read = |path: &str| fs::read_to_string(path).unwrap_or_default();
+        Self {
+            pid1_cgroup: read("/proc/1/cgroup"),
+            dockerenv: Path::new("/.dockerenv").exists(),
+            containerenv: Path::new("/run/.containerenv").exists(),
+            systemd_container: read("/run/systemd/container"),
+            kubernetes: env::var_os("KUBERNETES_SERVICE_HOST").is_some()
+                || Path::new("/var/run/secrets/kubernetes.io").exists(),
+            osrelease: read("/proc/sys/kernel/osrelease"),
+            wsl_env: env::var_os("WSL_DISTRO_NAME").is_some() || env::var_os("WSL_INTEROP").is_some(),
+            dmi_vendor: read("/sys/class/dmi/id/sys_vendor"),
+            dmi_product: read("/sys/class/dmi/id/product_name"),
+            hypervisor_type: read("/sys/hypervisor/type"),
+            hypervisor_flag: read("/proc/cpuinfo")
+                .lines()
+                .filter(|line| line.starts_with("flags"))
+                .any(|line| line.split_whitespace().any(|flag| flag == "hypervisor")),
+        }
+    }
+
+    fn classify(&self) -> RuntimeEnvironment {
+        RuntimeEnvironment {
+            container: self.container(),
+            virtualization: self.virtualization(),
+            wsl: self.wsl_env || self.osrelease.to_ascii_lowercase().contains("microsoft"),
+        }
+    }
+
+    /// Kubernetes 优先：Pod 里同样可能有 /.dockerenv 或 docker 的 cgroup 路径
+    fn container(&self) -> Option<Container> {
+        let cgroup = &self.pid1_cgroup;
+        if self.kubernetes || cgroup.contains("kubepods") {
+            return Some(Container::Kubernetes);
+        }
+        if self.containerenv || cgroup.contains("libpod") {
+            return Some(Container::Podman);
+        }
+        if self.dockerenv || cgroup.contains("/docker") || cgroup.contains("docker-") {
+            return Some(Container::Docker);
+        }
+        match self.systemd_container.trim() {
+            "" => None,
+            "systemd-nspawn" => Some(Container::SystemdNspawn),
+            "docker" => Some(Container::Docker),
+            "podman" => Some(Container::Podman),
+            name if name.starts_with("lxc") => Some(Container::Lxc),
+            name => Some(Container::Other(name.to_string())),
+        }
+    }
+
+    fn virtualization(&self) -> Option<Virtualization> {
+        let vendor = self.dmi_vendor.trim();
+        let product = self.dmi_product.trim();
+        let known = match (vendor, product) {
+            (_, "KVM") | (_, "OpenStack Compute") => Some(Virtualization::Kvm),
+            ("QEMU", _) => Some(Virtualization::Qemu),
+            ("VMware, Inc.", _) => Some(Virtualization::VMware),
+            ("innotek GmbH", _) | (_, "VirtualBox") => Some(Virtualization::VirtualBox),
+            ("Microsoft Corporation", "Virtual Machine") => Some(Virtualization::HyperV),
+            ("Xen", _) => Some(Virtualization::Xen),
+            _ => None,
+        };
+        known
+            .or_else(|| (self.hypervisor_type.trim() == "xen").then_some(Virtualization::Xen))
+            .or_else(|| {
+                // 物理机的 DMI 同样有厂商名（EC2 的 *.metal 实例也报告 "Amazon EC2"），
+                // 只有 CPU 报告了 hypervisor 标志才能认定是虚拟机
+                self.hypervisor_flag.then(|| match vendor {
+                    "" => Virtualization::Unknown,
+                    // EC2 的 Nitro 实例和 Google Compute Engine 都基于 KVM
+                    "Amazon EC2" | "Google" => Virtualization::Kvm,
+                    vendor => Virtualization::Other(vendor.to_string()),
+                })
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn containers_are_recognised_from_cgroups_and_marker_files() {
+        let docker = Evidence {
+            pid1_cgroup: "12:pids:/docker/3f4e8a\n0::/system.slice/docker-3f4e8a.scope\n".to_string(),
+            ..Evidence::default()
+        };
+        assert_eq!(docker.classify().container, Some(Container::Docker));
+
+        // cgroup v2 的私有命名空间中只能看到 0::/，这时依靠标记文件
+        let podman = Evidence { pid1_cgroup: "0::/\n".to_string(), containerenv: true, ..Evidence::default() };
+        assert_eq!(podman.classify().container, Some(Container::Podman));
+
+        let pod = Evidence {
+            pid1_cgroup: "0::/kubepods/burstable/pod1234/abcd\n".to_string(),
+            dockerenv: true,
+            ..Evidence::default()
+        };
+        assert_eq!(pod.classify().container, Some(Container::Kubernetes));
+
+        let nspawn = Evidence { systemd_container: "systemd-nspawn\n".to_string(), ..Evidence::default() };
+        assert_eq!(nspawn.classify().container, Some(Container::SystemdNspawn));
+        let other = Evidence { systemd_container: "rkt\n".to_string(), ..Evidence::default() };
+        assert_eq!(other.classify().container, Some(Container::Other("rkt".to_string())));
+    }
+
+    #[test]
+    fn virtual_machines_need_dmi_or_the_hypervisor_flag() {
+        let qemu = Evidence {
+            dmi_vendor: "QEMU\n".to_string(),
+            dmi_product: "Standard PC (Q35 + ICH9, 2009)\n".to_string(),
+            ..Evidence::default()
+        };
+        assert_eq!(qemu.classify().virtualization, Some(Virtualization::Qemu));
+
+        let hyperv = Evidence {
+            dmi_vendor: "Microsoft Corporation\n".to_string(),
+            dmi_product: "Virtual Machine\n".to_string(),
+            ..Evidence::default()
+        };
+        assert_eq!(hyperv.classify().virtualization, Some(Virtualization::HyperV));
+
+        // 物理机：有 DMI 厂商名但没有 hypervisor 标志
+        let bare = Evidence { dmi_vendor: "Dell Inc.\n".to_string(), ..Evidence::default() };
+        assert!(bare.classify().is_bare_metal());
+        assert_eq!(bare.classify().to_string(), "bare metal");
+
+        // EC2 的裸金属实例与虚拟机的 DMI 厂商名相同，区别只在 hypervisor 标志
+        let ec2 = |product: &str, hypervisor_flag| Evidence {
+            dmi_vendor: "Amazon EC2\n".to_string(),
+            dmi_product: format!("{}\n", product),
+            hypervisor_flag,
+            ..Evidence::default()
+        };
+        assert!(ec2("m5.metal", false).classify().is_bare_metal());
+        assert_eq!(ec2("m5.large", true).classify().virtualization, Some(Virtualization::Kvm));
+
+        // Firecracker 等没有 DMI 的虚拟机只能看到 hypervisor 标志
+        let microvm = Evidence { hypervisor_flag: true, dockerenv: true, ..Evidence::default() };
+        assert_eq!(microvm.classify().to_string(), "container=docker, vm=unknown");
+    }
+
+    #[test]
+    fn wsl_is_recognised_from_the_kernel_release() {
+        let wsl2 = Evidence {
+            osrelease: "5.15.153.1-microsoft-standard-WSL2\n".to_string(),
+            hypervisor_flag: true,
+            ..Evidence::default()
+        };
+        let environment = wsl2.classify();
+        assert!(environment.wsl && environment.is_virtualized() && !environment.is_containerized());
+
+        let wsl1 = Evidence { osrelease: "4.4.0-19041-Microsoft\n".to_string(), ..Evidence::default() };
+        assert!(wsl1.classify().wsl);
+    }
+}
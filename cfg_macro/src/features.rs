@@ -0,0 +1,121 @@
+/*!
+ * 通过 cargo feature 启用的代码
+ *
+ * 与 target_os 等由编译目标决定的条件不同，feature 由使用方在 Cargo.toml 中选择：
+ *
+ * ```toml
+ * [dependencies]
+ * cfg_macro = { path = "../cfg_macro", features = ["advanced-features"] }
+ * ```
+ *
+ * 没有启用对应 feature 时，AdvancedFeatureCode 和 CustomFeatureCode 都不存在。
+ */
+
+/// 编译时启用了哪些 cargo feature
+///
+/// 数组元素上的 #[cfg] 会在编译期把未启用的项去掉
+pub fn enabled_features() -> &'static [&'static str] {
+    &[
+        #[cfg(feature = "advanced-features")]
+        "advanced-features",
+        #[cfg(feature = "custom-feature")]
+        "custom-feature",
+    ]
+}
+
+// 需要自定义 feature 启用的代码（在 Cargo.toml 中定义）
+#[cfg(feature = "advanced-features")]
+pub struct AdvancedFeatureCode {
+    experimental_api: bool,
+    beta_features: bool,
+    debug_mode_enabled: bool,
+}
+
+// 高级功能实现
+#[cfg(feature = "advanced-features")]
+impl AdvancedFeatureCode {
+    pub fn new() -> Self {
+        Self {
+            experimental_api: cfg!(debug_assertions), // 调试模式下启用实验性 API
+            beta_features: true,
+            debug_mode_enabled: cfg!(debug_assertions),
+        }
+    }
+
+    pub fn is_experimental_enabled(&self) -> bool {
+        self.experimental_api
+    }
+
+    pub fn has_beta_features(&self) -> bool {
+        self.beta_features
+    }
+
+    pub fn is_debug_mode(&self) -> bool {
+        self.debug_mode_enabled
+    }
+}
+
+#[cfg(feature = "advanced-features")]
+impl Default for AdvancedFeatureCode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 用户自定义的能力探测（需要启用 custom-feature）
+///
+/// PlatformCapabilities 只覆盖通用的系统功能，项目自己关心的能力
+/// （某个设备文件是否存在、某个环境变量是否设置……）可以按名字注册到这里，
+/// 每次查询时重新执行探测函数。
+///
+/// ```rust
+/// # #[cfg(feature = "custom-feature")] {
+/// use cfg_macro::CustomFeatureCode;
+///
+/// let mut custom = CustomFeatureCode::new();
+/// custom.register("always", || true);
+/// assert_eq!(custom.check("always"), Some(true));
+/// assert_eq!(custom.check("missing"), None);
+/// # }
+/// ```
+#[cfg(feature = "custom-feature")]
+pub struct CustomFeatureCode {
+    probes: Vec<(String, Box<dyn Fn() -> bool>)>,
+}
+
+#[cfg(feature = "custom-feature")]
+impl CustomFeatureCode {
+    pub fn new() -> Self {
+        Self { probes: Vec::new() }
+    }
+
+    /// 注册一个探测函数；同名的探测会被替换
+    pub fn register(&mut self, name: impl Into<String>, probe: impl Fn() -> bool + 'static) -> &mut Self {
+        let name = name.into();
+        self.probes.retain(|(existing, _)| *existing != name);
+        self.probes.push((name, Box::new(probe)));
+        self
+    }
+
+    /// 按注册顺序列出所有探测的名字
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.probes.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// 执行一项探测；没有注册这个名字时返回 None
+    pub fn check(&self, name: &str) -> Option<bool> {
+        self.probes.iter().find(|(existing, _)| existing == name).map(|(_, probe)| probe())
+    }
+
+    /// 按注册顺序执行全部探测
+    pub fn run(&self) -> Vec<(&str, bool)> {
+        self.probes.iter().map(|(name, probe)| (name.as_str(), probe())).collect()
+    }
+}
+
+#[cfg(feature = "custom-feature")]
+impl Default for CustomFeatureCode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
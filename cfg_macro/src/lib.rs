@@ -0,0 +1,92 @@
+/*!
+ * Rust 条件编译完整演示
+ *
+ * 这个 crate 全面展示了 Rust 条件编译的各种用法，包括：
+ * - #[cfg(...)] 属性：编译时条件控制
+ * - cfg! 宏：运行时条件检查
+ * - 复杂条件组合：all(), any(), not()
+ * - 平台特定代码：跨平台开发技术
+ * - cargo feature：由使用方选择编译哪些功能
+ *
+ * 学习条件编译的重要性：
+ * 1. 跨平台开发的核心技术
+ * 2. 减少二进制文件大小
+ * 3. 提升性能（编译时优化）
+ * 4. 灵活的功能控制
+ *
+ * 模块划分：
+ * - platform：只在单个平台上存在的类型（LinuxCode、MacOSCode、WindowsCode）
+ * - capabilities：跨平台的 CrossPlatformCode 与运行时能力探测
+ * - report：机器可读的 JSON / TOML 能力报告
+ * - combinations：复杂条件组合（Linux64Code、UnixLikeCode、SpecificUnixCode 等）
+ * - environment：检测容器（Docker、Podman、Kubernetes、systemd-nspawn）、WSL 与虚拟机
+ * - posix：POSIX 版本、sysconf / pathconf 限制与 getrlimit 资源限制，以及最低要求的检查
+ * - simd：运行时检测 SIMD 指令集，并按检测结果分派函数实现
+ * - features：由 cargo feature 启用的代码
+ * - target：目标平台的运行时描述，可以替换为模拟的平台来测试其他平台的分支
+ * - cfg_expr：解析 cfg 谓词并在任意目标上求值，不需要调用 rustc
+ * - build_cfg：build.rs 探测构建环境后输出的自定义 cfg（has_io_uring、has_statx）
+ *
+ * 所有公开类型都在 crate 根部重新导出，每个类型只在满足自身条件的目标上存在。
+ *
+ * 相关文档：
+ * - https://doc.rust-lang.org/reference/conditional-compilation.html
+ * - https://doc.rust-lang.org/rust-by-example/attribute/cfg.html
+ * - https://doc.rust-lang.org/std/macro.cfg.html
+ */
+
+pub mod build_cfg;
+pub mod capabilities;
+pub mod cfg_expr;
+pub mod combinations;
+pub mod environment;
+pub mod features;
+#[cfg(target_os = "linux")]
+mod linux_probe;
+pub mod platform;
+#[cfg(not(target_os = "windows"))]
+pub mod posix;
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+pub mod report;
+pub mod simd;
+pub mod target;
+
+#[cfg(all(target_os = "linux", has_statx))]
+pub use build_cfg::birth_time;
+pub use build_cfg::build_cfgs;
+pub use capabilities::{Feature, FeatureError, SeccompMode};
+pub use cfg_expr::{CfgExpr, CfgParseError, CfgSet};
+pub use environment::{Container, RuntimeEnvironment, Virtualization};
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+pub use capabilities::{CrossPlatformCode, PlatformCapabilities};
+pub use features::enabled_features;
+pub use simd::{Dispatch, SimdFeature, SimdFeatures};
+pub use target::TargetPlatform;
+#[cfg(feature = "advanced-features")]
+pub use features::AdvancedFeatureCode;
+#[cfg(feature = "custom-feature")]
+pub use features::CustomFeatureCode;
+#[cfg(not(target_os = "windows"))]
+pub use posix::{LimitValue, PosixReport, Requirement, Resource, Rlimit, Shortfall, SysLimit};
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+pub use report::{ReportError, ReportFormat};
+
+#[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+pub use combinations::{Linux64Code, ThreadCount, ThreadCountSource};
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+pub use combinations::ApplePlatformCode;
+#[cfg(not(target_os = "windows"))]
+pub use combinations::UnixLikeCode;
+#[cfg(all(
+    unix,
+    not(target_os = "macos"),
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+pub use combinations::SpecificUnixCode;
+
+#[cfg(target_os = "linux")]
+pub use platform::LinuxCode;
+#[cfg(target_os = "macos")]
+pub use platform::MacOSCode;
+#[cfg(target_os = "windows")]
+pub use platform::WindowsCode;
@@ -0,0 +1,282 @@
+/*!
+ * Linux 运行时能力探测
+ *
+ * cfg!(target_os = "linux") 只能说明代码是为 Linux 编译的，
+ * 并不能说明正在运行的内核和沙箱真正允许什么：老内核没有 io_uring 和 Landlock，
+ * 容器的 seccomp 过滤器可能禁止某些系统调用，io_uring 也可能被 sysctl 关闭。
+ * 因此这里的每一项都实际尝试一次对应的系统调用，或者检查 /proc 与 /sys，
+ * 探测过程中创建的文件描述符都会立即关闭。
+ *
+ * 为了不引入第三方依赖，直接用 extern "C" 声明需要的 libc 函数；
+ * Rust 标准库在 Linux 上本来就链接了 libc。
+ */
+
+use crate::capabilities::SeccompMode;
+use std::ffi::{c_int, c_long, c_void};
+use std::fs;
+use std::path::Path;
+
+extern "C" {
+    fn epoll_create1(flags: c_int) -> c_int;
+    fn inotify_init1(flags: c_int) -> c_int;
+    fn close(fd: c_int) -> c_int;
+    fn prctl(option: c_int, ...) -> c_int;
+    fn syscall(number: c_long, ...) -> c_long;
+    fn sched_getaffinity(pid: c_int, cpusetsize: usize, mask: *mut c_void) -> c_int;
+}
+
+// 编号 424 之后新增的系统调用在各个架构上编号统一（mips 等少数架构有固定偏移）
+const SYS_IO_URING_SETUP: c_long = 425;
+const SYS_LANDLOCK_CREATE_RULESET: c_long = 444;
+const UNIFIED_SYSCALL_NUMBERS: bool =
+    !cfg!(any(target_arch = "mips", target_arch = "mips64", target_arch = "mips32r6", target_arch = "mips64r6"));
+
+const PR_GET_SECCOMP: c_int = 21;
+const LANDLOCK_CREATE_RULESET_VERSION: u32 = 1;
+
+/// 系统调用返回了有效的文件描述符时关闭它，并报告调用是否成功
+fn close_if_valid(fd: c_long) -> bool {
+    if fd < 0 {
+        return false;
+    }
+    unsafe {
+        close(fd as c_int);
+    }
+    true
+}
+
+/// 创建一个 epoll 实例
+pub fn epoll() -> bool {
+    close_if_valid(unsafe { epoll_create1(0) }.into())
+}
+
+/// 创建一个 inotify 实例
+pub fn inotify() -> bool {
+    close_if_valid(unsafe { inotify_init1(0) }.into())
+}
+
+/// 创建一个未绑定的 Unix 数据报套接字
+pub fn unix_sockets() -> bool {
+    std::os::unix::net::UnixDatagram::unbound().is_ok()
+}
+
+/// 用最小的队列调用 io_uring_setup
+///
+/// 内核不支持时返回 ENOSYS，被 kernel.io_uring_disabled 或 seccomp 禁止时返回 EPERM
+pub fn io_uring() -> bool {
+    if !UNIFIED_SYSCALL_NUMBERS {
+        return false;
+    }
+    // struct io_uring_params 共 120 字节，内核要求输入字段全部为 0
+    let mut params = [0u32; 30];
+    let fd = unsafe { syscall(SYS_IO_URING_SETUP, 1 as c_long, params.as_mut_ptr() as *mut c_void) };
+    close_if_valid(fd)
+}
+
+/// 查询内核支持的 Landlock ABI 版本，不支持或在启动参数中被关闭时返回 None
+pub fn landlock_abi() -> Option<u32> {
+    if !UNIFIED_SYSCALL_NUMBERS {
+        return None;
+    }
+    let version = unsafe {
+        syscall(
+            SYS_LANDLOCK_CREATE_RULESET,
+            std::ptr::null::<c_void>(),
+            0 as c_long,
+            LANDLOCK_CREATE_RULESET_VERSION as c_long,
+        )
+    };
+    u32::try_from(version).ok().filter(|v| *v > 0)
+}
+
+/// 当前进程的 seccomp 模式；内核没有编译 seccomp 支持时返回 None
+///
+/// 优先读取 /proc/self/status 中的 Seccomp 字段；/proc 不可用时
+/// （某些沙箱不挂载 /proc）退回 prctl(PR_GET_SECCOMP)：内核不支持时它返回 EINVAL，
+/// 否则返回 0 或 2（严格模式下 prctl 本身就被禁止，进程会被 SIGKILL，所以不会得到 1）
+pub fn seccomp_mode() -> Option<SeccompMode> {
+    if let Ok(status) = fs::read_to_string("/proc/self/status") {
+        return parse_seccomp_mode(&status);
+    }
+    match unsafe { prctl(PR_GET_SECCOMP) } {
+        0 => Some(SeccompMode::Disabled),
+        2 => Some(SeccompMode::Filter),
+        _ => None,
+    }
+}
+
+/// 解析 /proc/self/status 中的 "Seccomp:        2" 这样的行
+fn parse_seccomp_mode(status: &str) -> Option<SeccompMode> {
+    let value = status.lines().find_map(|line| line.strip_prefix("Seccomp:"))?;
+    match value.trim() {
+        "0" => Some(SeccompMode::Disabled),
+        "1" => Some(SeccompMode::Strict),
+        "2" => Some(SeccompMode::Filter),
+        _ => None,
+    }
+}
+
+/// 是否挂载了 cgroup v2（统一层级）
+///
+/// 标准位置 /sys/fs/cgroup 下存在 cgroup.controllers 即为统一层级；
+/// 否则再检查 /proc/self/mounts 中有没有挂载在其他位置的 cgroup2 文件系统
+pub fn cgroup_v2() -> bool {
+    if Path::new("/sys/fs/cgroup/cgroup.controllers").exists() {
+        return true;
+    }
+    fs::read_to_string("/proc/self/mounts")
+        .map(|mounts| mounts.lines().any(|line| line.split_whitespace().nth(2) == Some("cgroup2")))
+        .unwrap_or(false)
+}
+
+/// 当前线程的 CPU 亲和性掩码中的 CPU 数量
+///
+/// taskset、docker --cpuset-cpus 和 Kubernetes 的静态 CPU 管理策略都通过亲和性限制 CPU。
+/// 掩码的长度取决于内核配置的 CPU 上限，缓冲区太小时内核返回 EINVAL，这时加倍重试。
+pub fn cpu_affinity_count() -> Option<usize> {
+    let mut words = 16; // 1024 个 CPU，与 glibc 的 cpu_set_t 相同
+    while words <= 1 << 16 {
+        let mut mask = vec![0u64; words];
+        let size = words * std::mem::size_of::<u64>();
+        if unsafe { sched_getaffinity(0, size, mask.as_mut_ptr().cast()) } == 0 {
+            let count: u32 = mask.iter().map(|word| word.count_ones()).sum();
+            return (count > 0).then_some(count as usize);
+        }
+        words *= 2;
+    }
+    None
+}
+
+/// cgroup v2 的 CPU 配额，向上取整为 CPU 数量；没有配额时返回 None
+///
+/// 配额可以设在任何一级祖先 cgroup 上，所以从当前 cgroup 一直检查到根，取最小值。
+pub fn cgroup_v2_cpu_limit() -> Option<usize> {
+    let cgroups = fs::read_to_string("/proc/self/cgroup").ok()?;
+    let path = cgroups.lines().find_map(|line| line.strip_prefix("0::"))?;
+    let root = Path::new("/sys/fs/cgroup");
+    min_limit_to_root(&root.join(path.trim_start_matches('/')), root, |dir| {
+        parse_cpu_max(&fs::read_to_string(dir.join("cpu.max")).ok()?)
+    })
+}
+
+/// cgroup v1 的 CPU 配额（cpu.cfs_quota_us / cpu.cfs_period_us）；没有配额时返回 None
+///
+/// cpu 控制器常见的挂载点有 cpu、cpu,cpuacct 和 cpuacct,cpu 几种写法。
+/// 与 v2 一样，systemd 的 slice 和 Kubernetes 的 Pod / 容器层级都可能把配额设在中间某一级，
+/// 所以从当前 cgroup 一直检查到挂载点根目录，取最小值。容器内通常把自己的 cgroup
+/// 直接挂在挂载点根目录，而 /proc/self/cgroup 里仍是宿主机上的路径，这时只有根目录存在。
+pub fn cgroup_v1_cpu_limit() -> Option<usize> {
+    let cgroups = fs::read_to_string("/proc/self/cgroup").ok()?;
+    let path = cgroups.lines().find_map(|line| {
+        let mut parts = line.splitn(3, ':');
+        let (_, controllers, path) = (parts.next()?, parts.next()?, parts.next()?);
+        controllers.split(',').any(|c| c == "cpu").then_some(path)
+    })?;
+    let root = ["cpu", "cpu,cpuacct", "cpuacct,cpu"]
+        .iter()
+        .map(|mount| Path::new("/sys/fs/cgroup").join(mount))
+        .find(|root| root.is_dir())?;
+    min_limit_to_root(&root.join(path.trim_start_matches('/')), &root, |dir| {
+        let quota = fs::read_to_string(dir.join("cpu.cfs_quota_us")).ok()?;
+        let period = fs::read_to_string(dir.join("cpu.cfs_period_us")).ok()?;
+        parse_cfs_quota(&quota, &period)
+    })
+}
+
+/// 从 `leaf` 逐级向上直到 `root`，对每一级调用 `limit`，返回其中的最小值
+fn min_limit_to_root(leaf: &Path, root: &Path, limit: impl Fn(&Path) -> Option<usize>) -> Option<usize> {
+    let mut dir = leaf.to_path_buf();
+    let mut min: Option<usize> = None;
+    loop {
+        if let Some(cpus) = limit(&dir) {
+            min = Some(min.map_or(cpus, |current| current.min(cpus)));
+        }
+        if dir == root || !dir.pop() {
+            return min;
+        }
+    }
+}
+
+/// 解析 cgroup v2 的 cpu.max，格式为 "$MAX $PERIOD"，其中 $MAX 可以是 "max"
+fn parse_cpu_max(content: &str) -> Option<usize> {
+    let mut fields = content.split_whitespace();
+    let quota = fields.next()?;
+    let period = fields.next().unwrap_or("100000");
+    parse_cfs_quota(quota, period)
+}
+
+/// 配额除以周期并向上取整，至少为 1；配额为 -1 或 max 表示不限制
+fn parse_cfs_quota(quota: &str, period: &str) -> Option<usize> {
+    let quota: u64 = quota.trim().parse().ok()?;
+    let period: u64 = period.trim().parse().ok()?;
+    if quota == 0 || period == 0 {
+        return None;
+    }
+    Some((quota.div_ceil(period) as usize).max(1))
+}
+
+/// /proc/meminfo 中的 MemTotal，换算成字节
+pub fn mem_total_bytes() -> Option<u64> {
+    let meminfo = fs::read_to_string("/proc/meminfo").ok()?;
+    parse_mem_total(&meminfo)
+}
+
+/// 解析 "MemTotal:       16318048 kB" 这样的行
+fn parse_mem_total(meminfo: &str) -> Option<u64> {
+    let line = meminfo.lines().find_map(|line| line.strip_prefix("MemTotal:"))?;
+    let kib: u64 = line.trim().strip_suffix("kB")?.trim().parse().ok()?;
+    kib.checked_mul(1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cpu_quota_rounds_up_and_ignores_unlimited() {
+        assert_eq!(parse_cpu_max("max 100000\n"), None);
+        assert_eq!(parse_cpu_max("200000 100000\n"), Some(2));
+        assert_eq!(parse_cpu_max("150000 100000"), Some(2));
+        assert_eq!(parse_cpu_max("5000 100000"), Some(1));
+        assert_eq!(parse_cfs_quota("-1\n", "100000\n"), None);
+        assert_eq!(parse_cfs_quota("400000\n", "100000\n"), Some(4));
+    }
+
+    #[test]
+    fn the_smallest_quota_on_the_way_to_the_root_wins() {
+        let root = Path::new("/sys/fs/cgroup/cpu,cpuacct");
+        let leaf = root.join("kubepods/pod1/container1");
+        let quotas = |limits: &'static [(&'static str, usize)]| {
+            move |dir: &Path| {
+                let relative = dir.strip_prefix(root).ok()?.to_str()?;
+                limits.iter().find(|(path, _)| *path == relative).map(|(_, cpus)| *cpus)
+            }
+        };
+        // 配额设在中间的 Pod 一级
+        assert_eq!(min_limit_to_root(&leaf, root, quotas(&[("kubepods/pod1", 2)])), Some(2));
+        assert_eq!(
+            min_limit_to_root(&leaf, root, quotas(&[("kubepods/pod1/container1", 4), ("kubepods", 3), ("", 8)])),
+            Some(3)
+        );
+        assert_eq!(min_limit_to_root(&leaf, root, quotas(&[])), None);
+        // 不会越过根目录继续向上
+        assert_eq!(min_limit_to_root(&leaf, root, |dir| (dir == Path::new("/sys/fs")).then_some(1)), None);
+    }
+
+    #[test]
+    fn seccomp_mode_comes_from_the_status_value() {
+        let status = |value: &str| format!("Name:\tcfg_macro\nSeccomp:\t{}\nSeccomp_filters:\t1\n", value);
+        assert_eq!(parse_seccomp_mode(&status("0")), Some(SeccompMode::Disabled));
+        assert_eq!(parse_seccomp_mode(&status("1")), Some(SeccompMode::Strict));
+        assert_eq!(parse_seccomp_mode(&status("2")), Some(SeccompMode::Filter));
+        // 内核没有 CONFIG_SECCOMP 时没有这一行
+        assert_eq!(parse_seccomp_mode("Name:\tcfg_macro\nNoNewPrivs:\t0\n"), None);
+    }
+
+    #[test]
+    fn mem_total_is_converted_to_bytes() {
+        let meminfo = "MemTotal:       16318048 kB\nMemFree:         1234 kB\n";
+        assert_eq!(parse_mem_total(meminfo), Some(16318048 * 1024));
+        assert_eq!(parse_mem_total("MemFree: 1 kB\n"), None);
+    }
+}
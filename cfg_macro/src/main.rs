@@ -0,0 +1,287 @@
+/*!
+ * 条件编译演示程序
+ *
+ * 依次演示 cfg! 宏、跨平台代码、目标系统信息、条件组合、cargo feature 和编译模式。
+ *
+ * 运行方式：
+ * - cargo run                              默认（不启用任何 feature）
+ * - cargo run --all-features               同时启用 advanced-features 和 custom-feature
+ * - cargo run --release                    发布模式
+ * - cargo run -- --report json|toml        只输出机器可读的能力报告
+ */
+
+#[cfg(feature = "advanced-features")]
+use cfg_macro::AdvancedFeatureCode;
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+use cfg_macro::CrossPlatformCode;
+#[cfg(feature = "custom-feature")]
+use cfg_macro::CustomFeatureCode;
+#[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+use cfg_macro::Linux64Code;
+#[cfg(target_os = "linux")]
+use cfg_macro::LinuxCode;
+#[cfg(target_os = "macos")]
+use cfg_macro::MacOSCode;
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+use cfg_macro::ReportFormat;
+#[cfg(all(
+    unix,
+    not(target_os = "macos"),
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+use cfg_macro::SpecificUnixCode;
+use cfg_macro::{build_cfgs, enabled_features};
+#[cfg(not(target_os = "windows"))]
+use cfg_macro::{Requirement, Resource, UnixLikeCode};
+use cfg_macro::{SimdFeatures, TargetPlatform};
+use std::env;
+
+// ========== 第三部分：编译模式相关代码 ==========
+
+/**
+ * 调试模式专用函数
+ *
+ * debug_assertions 是一个内置的条件，当编译器不包含优化时为 true
+ * 这意味着：
+ * - cargo build (debug模式) -> debug_assertions = true
+ * - cargo build --release (release模式) -> debug_assertions = false
+ *
+ * 适用场景：
+ * - 开发期间的调试信息
+ * - 性能测试代码
+ * - 开发工具和辅助函数
+ */
+#[cfg(debug_assertions)]
+fn debug_info() {
+    println!("🔍 这是调试模式下的信息");
+    println!("✅ 调试功能已启用");
+    println!("📊 性能监控已开启");
+    println!("🪲 日志级别: DEBUG");
+
+    // 在调试模式下，我们可以包含更多检查和输出
+    println!("🔧 开发者选项:");
+    println!("   - 详细的错误堆栈跟踪");
+    println!("   - 内存分配跟踪");
+    println!("   - 性能分析工具");
+}
+
+/**
+ * 发布模式专用函数
+ *
+ * not(debug_assertions) 表示不在调试模式，即发布模式
+ * 发布模式的特点：
+ * - 编译器优化开启
+ * - 调试信息被移除
+ * - panic 信息可能被简化
+ * - 更小的二进制文件和更好的性能
+ *
+ * 适用场景：
+ * - 生产环境信息
+ * - 性能优化通知
+ * - 用户友好的错误消息
+ */
+#[cfg(not(debug_assertions))]
+fn release_info() {
+    println!("🚀 这是发布模式下的信息");
+    println!("⚡ 优化版本正在运行");
+    println!("🔒 生产环境配置已加载");
+    println!("📈 性能优化已启用");
+
+    // 发布模式的特性
+    println!("🎯 生产特性:");
+    println!("   - 编译器优化已开启");
+    println!("   - 内存使用优化");
+    println!("   - 执行速度优化");
+    println!("   - 错误处理简化");
+}
+
+fn main() {
+    // 命令行：--report json|toml 只输出机器可读的能力报告，供部署脚本使用
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let Some(position) = args.iter().position(|arg| arg == "--report") {
+        #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+        {
+            let format = args.get(position + 1).map(String::as_str).unwrap_or("json");
+            match format.parse::<ReportFormat>() {
+                Ok(format) => print!("{}", CrossPlatformCode::new().report(format)),
+                Err(err) => {
+                    eprintln!("error: {}", err);
+                    std::process::exit(2);
+                }
+            }
+            return;
+        }
+        #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+        {
+            let _ = position;
+            eprintln!("error: capability reports are not available on {}", env::consts::OS);
+            std::process::exit(1);
+        }
+    }
+
+    println!("=== 条件编译和 cfg! 宏演示 ===\n");
+
+    // ========== cfg! 宏示例（运行时检查）==========
+    println!("1. cfg! 宏运行时检查：");
+
+    if cfg!(target_os = "macos") {
+        println!("→ 运行 macOS 特定的代码");
+
+        // 因为前面已经用 #[cfg(target_os = "macos")] 条件编译了 MacOSCode
+        // 所以在这里可以安全使用
+        #[cfg(target_os = "macos")]
+        {
+            let macos_code = MacOSCode::new();
+            println!("→ 创建了 macOS 专用代码: {}", macos_code.version());
+        }
+    }
+    else if cfg!(target_os = "linux") {
+        println!("→ 运行 Linux 特定的代码");
+
+        #[cfg(target_os = "linux")]
+        {
+            let linux_code = LinuxCode::new();
+            println!("→ 创建了 Linux 专用代码: {}", linux_code.version());
+        }
+    } else {
+        println!("→ 我们不完全支持这个操作系统");
+    }
+
+    println!();
+
+    // ========== 跨平台代码示例 ==========
+    println!("2. 跨平台代码示例：");
+
+    #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+    {
+        let cross_platform = CrossPlatformCode::new();
+        cross_platform.greet();
+
+        // 按名字查询：拼写错误和其他平台的功能会得到不同的错误，而不是 false
+        for name in ["epoll", "metal", "epol"] {
+            match cross_platform.supports_feature(name) {
+                Ok(supported) => println!("   {} 支持: {}", name, supported),
+                Err(err) => println!("   {} 无法查询: {}", name, err),
+            }
+        }
+        let available: Vec<_> = cross_platform
+            .features()
+            .filter(|(_, supported)| *supported)
+            .map(|(feature, _)| feature.name())
+            .collect();
+        println!("   本平台可用的功能: {}", available.join(", "));
+    }
+
+    println!();
+
+    // ========== 系统信息显示 ==========
+    println!("3. 当前目标系统信息：");
+    let host = TargetPlatform::host();
+    println!("   操作系统: {}", host.os);
+    println!("   架构: {}", host.arch);
+    println!("   指针宽度: {} 位", host.pointer_width);
+    println!("   系列家族: {}", host.family);
+
+    // 容器和虚拟机只能在运行时推断，系统限制也要在运行时查询
+    #[cfg(not(target_os = "windows"))]
+    {
+        let unix = UnixLikeCode::new();
+        println!("   运行环境: {}", unix.environment());
+        let posix = unix.posix();
+        println!("   POSIX 版本: {}", posix.edition().unwrap_or("未知"));
+        for (limit, value) in &posix.limits {
+            println!("   {}: {}", limit, value);
+        }
+        for (resource, rlimit) in &posix.resources {
+            println!("   {}: {}（硬限制 {}）", resource, rlimit.soft, rlimit.hard);
+        }
+        let shortfalls = posix.check(&[Requirement::Resource(Resource::OpenFiles, 65536)]);
+        for shortfall in shortfalls {
+            println!("   ⚠ {}", shortfall);
+        }
+    }
+
+    // ========== 条件编译功能演示 ==========
+    println!("\n4. 条件编译功能演示：");
+
+    // 使用 cfg! 宏检查各种条件
+    println!("   是否为 64 位系统: {}", cfg!(target_pointer_width = "64"));
+    println!("   是否为 Unix 系统: {}", cfg!(unix));
+    println!("   是否为 Windows 系统: {}", cfg!(windows));
+    println!("   是否为调试模式: {}", cfg!(debug_assertions));
+
+    // 根据架构显示不同信息
+    if cfg!(target_arch = "x86_64") {
+        println!("   运行在 x86_64 架构上");
+    } else if cfg!(target_arch = "aarch64") {
+        println!("   运行在 ARM64/aarch64 架构上");
+    } else if cfg!(target_arch = "x86") {
+        println!("   运行在 x86 架构上");
+    }
+
+    // 容器里按宿主机的 CPU 数开线程会超出配额，这里同时考虑亲和性和 cgroup 配额
+    #[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+    {
+        let count = Linux64Code::new().thread_count();
+        println!("   推荐线程数: {}（由 {} 决定）", count.threads, count.source);
+    }
+
+    // 编译时的 target_feature 只保证最低的指令集，实际可用的 SIMD 要在运行时检测
+    println!("   SIMD 指令集: {}", SimdFeatures::detect());
+    #[cfg(all(
+        unix,
+        not(target_os = "macos"),
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    ))]
+    {
+        let code = SpecificUnixCode::new();
+        println!("   {} 优化级别: {}", code.get_variant(), code.optimization_level());
+    }
+
+    // ========== cargo feature ==========
+    println!("\n5. cargo feature：");
+    let enabled = enabled_features();
+    if enabled.is_empty() {
+        println!("   未启用任何 feature（试试 cargo run --all-features）");
+    } else {
+        println!("   已启用: {}", enabled.join(", "));
+    }
+
+    #[cfg(feature = "advanced-features")]
+    {
+        let advanced = AdvancedFeatureCode::new();
+        println!("   实验性 API: {}", advanced.is_experimental_enabled());
+        println!("   测试版功能: {}", advanced.has_beta_features());
+    }
+
+    #[cfg(feature = "custom-feature")]
+    {
+        let mut custom = CustomFeatureCode::new();
+        custom
+            .register("docker", || std::path::Path::new("/.dockerenv").exists())
+            .register("ci", || env::var_os("CI").is_some());
+        for (name, present) in custom.run() {
+            println!("   自定义探测 {}: {}", name, present);
+        }
+    }
+
+    // build.rs 探测构建环境后输出的 cfg
+    let build = build_cfgs();
+    println!(
+        "   构建环境: {}（glibc {}）",
+        if build.is_empty() { "无".to_string() } else { build.join(", ") },
+        cfg_macro::build_cfg::GLIBC_VERSION.unwrap_or("未知")
+    );
+
+    // ========== 条件编译的函数调用 ==========
+    println!("\n6. 条件编译函数调用：");
+
+    // 这些函数只有在相应的条件下才会被编译
+    #[cfg(debug_assertions)]
+    debug_info();
+
+    #[cfg(not(debug_assertions))]
+    release_info();
+
+    println!("\n=== 演示完成 ===");
+}
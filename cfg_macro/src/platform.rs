@@ -0,0 +1,159 @@
+/*!
+ * 第一部分：基础条件编译属性
+ *
+ * 每个平台各有一个只在该平台上编译的类型，在其他平台上这些类型完全不存在。
+ */
+
+/**
+ * Linux 平台专用代码示例
+ *
+ * #[cfg(target_os = "linux")] 是条件编译属性，表示这段代码只会在
+ * 目标操作系统为 Linux 时被编译器包含。在其他平台上，这段代码
+ * 完全不存在，不会占用任何空间。
+ *
+ * 条件编译的作用：
+ * - 零开销：不满足条件的代码不会被编译
+ * - 平台优化：可以为不同平台提供最优实现
+ * - 代码隔离：避免在不支持的平台上编译错误
+ */
+#[cfg(target_os = "linux")]
+pub struct LinuxCode {
+    /// 版本信息字符串
+    version: String,
+    /// 平台特定功能标志
+    features_enabled: bool,
+}
+
+#[cfg(target_os = "linux")]
+impl LinuxCode {
+    /// 创建新的 LinuxCode 实例
+    ///
+    /// 这个构造函数也只会在 Linux 平台上编译
+    ///
+    /// # 返回值
+    /// 返回一个初始化的 LinuxCode 实例
+    pub fn new() -> Self {
+        Self {
+            version: "Linux Edition v2.0".to_string(),
+            features_enabled: true, // Linux 默认启用所有功能
+        }
+    }
+
+    /// 获取平台特定功能
+    #[cfg(target_os = "linux")]
+    pub fn get_linux_features(&self) -> Vec<&'static str> {
+        vec![
+            "epoll 支持",
+            "inotify 文件监控",
+            "信号处理",
+            "Unix 域套接字"
+        ]
+    }
+
+    /// 版本信息字符串
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// 是否启用了平台特定功能
+    pub fn features_enabled(&self) -> bool {
+        self.features_enabled
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Default for LinuxCode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/**
+ * macOS 平台专用代码示例
+ *
+ * 与 LinuxCode 类似，这个结构体只在 macOS 平台上编译
+ * 展示了条件编译在不同平台上的应用
+ */
+#[cfg(target_os = "macos")]
+pub struct MacOSCode {
+    /// 版本信息
+    version: String,
+    /// 系统集成等级
+    integration_level: u8,
+}
+
+#[cfg(target_os = "macos")]
+impl MacOSCode {
+    /// 创建新的 MacOSCode 实例
+    pub fn new() -> Self {
+        Self {
+            version: "macOS Edition v2.0".to_string(),
+            integration_level: 3, // macOS 系统集成等级
+        }
+    }
+
+    /// 获取 macOS 特有功能
+    #[cfg(target_os = "macos")]
+    pub fn get_macos_features(&self) -> Vec<&'static str> {
+        vec![
+            "Grand Central Dispatch",
+            "Metal 图形 API",
+            "Core Foundation",
+            "Mach 端口通信"
+        ]
+    }
+
+    /// 版本信息
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// 系统集成等级
+    pub fn integration_level(&self) -> u8 {
+        self.integration_level
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl Default for MacOSCode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/**
+ * Windows 平台专用代码示例（预留扩展）
+ *
+ * 这个结构体目前为空，展示如何为其他平台预留接口
+ * 当需要支持 Windows 时，可以补充实现
+ */
+#[cfg(target_os = "windows")]
+pub struct WindowsCode {
+    version: String,
+    registry_access: bool,
+}
+
+#[cfg(target_os = "windows")]
+impl WindowsCode {
+    pub fn new() -> Self {
+        Self {
+            version: "Windows Edition v2.0".to_string(),
+            registry_access: true,
+        }
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn has_registry_access(&self) -> bool {
+        self.registry_access
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl Default for WindowsCode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
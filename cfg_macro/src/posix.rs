@@ -0,0 +1,473 @@
+/*!
+ * POSIX 版本与系统限制
+ *
+ * 同样是 Unix，不同系统（以及同一系统的不同配置）给进程的限制差别很大：
+ * 能打开多少个文件、命令行参数能有多长、栈有多大。这些值只能在运行时查询：
+ *
+ * - `sysconf`：系统范围的配置，例如 `_SC_OPEN_MAX`、`_SC_PAGESIZE`、`_SC_ARG_MAX`；
+ * - `pathconf`：与文件系统相关的限制，例如根目录下的 `_PC_PATH_MAX`；
+ * - `getrlimit`：当前进程的资源限制，分为软限制（实际生效）和硬限制（软限制能提高到的上限）。
+ *
+ * `sysconf` 等函数的参数编号在各个系统上并不相同，这里为 Linux / Android、macOS / iOS 和 FreeBSD
+ * 分别声明；其他系统上报告为空。
+ *
+ * ```rust
+ * # #[cfg(target_os = "linux")] {
+ * use cfg_macro::posix::{PosixReport, Requirement, Resource, SysLimit};
+ *
+ * let report = PosixReport::detect();
+ * let shortfalls = report.check(&[
+ *     Requirement::Version(200112),
+ *     Requirement::Limit(SysLimit::PageSize, 4096),
+ *     Requirement::Resource(Resource::OpenFiles, 64),
+ * ]);
+ * for shortfall in &shortfalls {
+ *     eprintln!("{}", shortfall);
+ * }
+ * # }
+ * ```
+ */
+
+use std::fmt;
+
+/// sysconf / pathconf 查询的系统限制
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SysLimit {
+    /// 每个进程能打开的文件数，`sysconf(_SC_OPEN_MAX)`
+    OpenFiles,
+    /// 内存页大小（字节），`sysconf(_SC_PAGESIZE)`
+    PageSize,
+    /// 路径的最大长度（字节），`pathconf("/", _PC_PATH_MAX)`
+    PathMax,
+    /// exec 的参数与环境变量总长度（字节），`sysconf(_SC_ARG_MAX)`
+    ArgMax,
+    /// 每秒的时钟滴答数，`sysconf(_SC_CLK_TCK)`
+    ClockTicks,
+}
+
+impl SysLimit {
+    pub const ALL: [SysLimit; 5] =
+        [SysLimit::OpenFiles, SysLimit::PageSize, SysLimit::PathMax, SysLimit::ArgMax, SysLimit::ClockTicks];
+
+    /// 对应的 C 常量名
+    pub fn name(self) -> &'static str {
+        match self {
+            SysLimit::OpenFiles => "_SC_OPEN_MAX",
+            SysLimit::PageSize => "_SC_PAGESIZE",
+            SysLimit::PathMax => "_PC_PATH_MAX",
+            SysLimit::ArgMax => "_SC_ARG_MAX",
+            SysLimit::ClockTicks => "_SC_CLK_TCK",
+        }
+    }
+}
+
+impl fmt::Display for SysLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// getrlimit 查询的进程资源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Resource {
+    /// 文件描述符数量，`RLIMIT_NOFILE`
+    OpenFiles,
+    /// 主线程栈大小（字节），`RLIMIT_STACK`
+    Stack,
+    /// 同一用户的进程数，`RLIMIT_NPROC`
+    Processes,
+    /// 虚拟地址空间（字节），`RLIMIT_AS`
+    AddressSpace,
+}
+
+impl Resource {
+    pub const ALL: [Resource; 4] = [Resource::OpenFiles, Resource::Stack, Resource::Processes, Resource::AddressSpace];
+
+    /// 对应的 C 常量名
+    pub fn name(self) -> &'static str {
+        match self {
+            Resource::OpenFiles => "RLIMIT_NOFILE",
+            Resource::Stack => "RLIMIT_STACK",
+            Resource::Processes => "RLIMIT_NPROC",
+            Resource::AddressSpace => "RLIMIT_AS",
+        }
+    }
+}
+
+impl fmt::Display for Resource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// 限制的取值
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LimitValue {
+    Finite(u64),
+    /// 没有限制（RLIM_INFINITY，或者 sysconf / pathconf 报告不确定的限制）
+    Unlimited,
+}
+
+impl LimitValue {
+    /// 是否不低于 `minimum`
+    pub fn at_least(self, minimum: u64) -> bool {
+        match self {
+            LimitValue::Finite(value) => value >= minimum,
+            LimitValue::Unlimited => true,
+        }
+    }
+}
+
+impl fmt::Display for LimitValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LimitValue::Finite(value) => write!(f, "{}", value),
+            LimitValue::Unlimited => f.write_str("unlimited"),
+        }
+    }
+}
+
+/// 一项资源的软限制与硬限制
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rlimit {
+    /// 实际生效的限制
+    pub soft: LimitValue,
+    /// 非特权进程能把软限制提高到的上限
+    pub hard: LimitValue,
+}
+
+/// 对系统的一项最低要求
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Requirement {
+    /// `_POSIX_VERSION` 不低于给定值，例如 200809 表示 POSIX.1-2008
+    Version(u32),
+    /// sysconf / pathconf 限制不低于给定值
+    Limit(SysLimit, u64),
+    /// 资源的软限制不低于给定值
+    Resource(Resource, u64),
+}
+
+impl fmt::Display for Requirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Requirement::Version(version) => write!(f, "_POSIX_VERSION >= {}", version),
+            Requirement::Limit(limit, minimum) => write!(f, "{} >= {}", limit, minimum),
+            Requirement::Resource(resource, minimum) => write!(f, "{} >= {}", resource, minimum),
+        }
+    }
+}
+
+/// 没有满足的要求
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Shortfall {
+    pub requirement: Requirement,
+    /// 实际的值；无法查询时为 None（同样视为不满足）
+    pub actual: Option<u64>,
+    /// 资源限制：硬限制满足要求，可以用 setrlimit 提高软限制来解决
+    pub raisable: bool,
+}
+
+impl fmt::Display for Shortfall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.actual {
+            Some(actual) => write!(f, "requires {}, found {}", self.requirement, actual)?,
+            None => write!(f, "requires {}, but it could not be determined", self.requirement)?,
+        }
+        if self.raisable {
+            f.write_str(" (the hard limit allows raising it)")?;
+        }
+        Ok(())
+    }
+}
+
+/// POSIX 版本与系统限制的报告
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PosixReport {
+    /// `sysconf(_SC_VERSION)`，例如 200809；无法查询时为 None
+    pub version: Option<u32>,
+    /// 查询成功的 sysconf / pathconf 限制
+    pub limits: Vec<(SysLimit, LimitValue)>,
+    /// 查询成功的资源限制
+    pub resources: Vec<(Resource, Rlimit)>,
+}
+
+impl PosixReport {
+    /// 查询当前系统与当前进程
+    pub fn detect() -> Self {
+        #[cfg(any(
+            target_os = "linux",
+            target_os = "android",
+            target_os = "macos",
+            target_os = "ios",
+            target_os = "freebsd"
+        ))]
+        {
+            Self {
+                version: match sys::sysconf(sys::SC_VERSION) {
+                    LimitValue::Finite(version) => u32::try_from(version).ok(),
+                    LimitValue::Unlimited => None,
+                },
+                limits: SysLimit::ALL.into_iter().map(|limit| (limit, sys::limit(limit))).collect(),
+                resources: Resource::ALL
+                    .into_iter()
+                    .filter_map(|resource| Some((resource, sys::rlimit(resource)?)))
+                    .collect(),
+            }
+        }
+        #[cfg(not(any(
+            target_os = "linux",
+            target_os = "android",
+            target_os = "macos",
+            target_os = "ios",
+            target_os = "freebsd"
+        )))]
+        {
+            Self::default()
+        }
+    }
+
+    /// POSIX 标准的版本名，例如 200809 对应 "POSIX.1-2008"
+    pub fn edition(&self) -> Option<&'static str> {
+        Some(match self.version? {
+            version if version >= 202405 => "POSIX.1-2024",
+            version if version >= 200809 => "POSIX.1-2008",
+            version if version >= 200112 => "POSIX.1-2001",
+            version if version >= 199506 => "POSIX.1-1996",
+            version if version >= 199009 => "POSIX.1-1990",
+            _ => "POSIX.1-1988",
+        })
+    }
+
+    pub fn limit(&self, limit: SysLimit) -> Option<LimitValue> {
+        self.limits.iter().find(|(l, _)| *l == limit).map(|(_, value)| *value)
+    }
+
+    pub fn resource(&self, resource: Resource) -> Option<Rlimit> {
+        self.resources.iter().find(|(r, _)| *r == resource).map(|(_, rlimit)| *rlimit)
+    }
+
+    /// 逐项检查最低要求，返回没有满足的项；全部满足时返回空列表
+    ///
+    /// 无法查询的项同样视为不满足；资源限制比较的是软限制
+    pub fn check(&self, requirements: &[Requirement]) -> Vec<Shortfall> {
+        requirements
+            .iter()
+            .filter_map(|&requirement| {
+                let finite = |value: LimitValue| match value {
+                    LimitValue::Finite(value) => Some(value),
+                    LimitValue::Unlimited => None,
+                };
+                let shortfall = |actual, raisable| Some(Shortfall { requirement, actual, raisable });
+                match requirement {
+                    Requirement::Version(minimum) => match self.version {
+                        Some(version) if version >= minimum => None,
+                        version => shortfall(version.map(u64::from), false),
+                    },
+                    Requirement::Limit(limit, minimum) => match self.limit(limit) {
+                        Some(value) if value.at_least(minimum) => None,
+                        value => shortfall(value.and_then(finite), false),
+                    },
+                    Requirement::Resource(resource, minimum) => match self.resource(resource) {
+                        Some(rlimit) if rlimit.soft.at_least(minimum) => None,
+                        Some(rlimit) => shortfall(finite(rlimit.soft), rlimit.hard.at_least(minimum)),
+                        None => shortfall(None, false),
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "macos", target_os = "ios", target_os = "freebsd"))]
+mod sys {
+    use super::{LimitValue, Resource, Rlimit, SysLimit};
+    use std::ffi::{c_char, c_int, c_long};
+
+    // glibc 和 bionic 的 rlim_t 是 unsigned long（32 位目标上为 32 位），其他系统都是 64 位
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    type RlimT = std::ffi::c_ulong;
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    type RlimT = u64;
+
+    #[repr(C)]
+    struct RawRlimit {
+        cur: RlimT,
+        max: RlimT,
+    }
+
+    extern "C" {
+        #[link_name = "sysconf"]
+        fn c_sysconf(name: c_int) -> c_long;
+        fn pathconf(path: *const c_char, name: c_int) -> c_long;
+        fn getrlimit(resource: c_int, rlim: *mut RawRlimit) -> c_int;
+    }
+
+    #[cfg(target_os = "linux")]
+    mod consts {
+        use std::ffi::c_int;
+
+        pub const SC_ARG_MAX: c_int = 0;
+        pub const SC_CLK_TCK: c_int = 2;
+        pub const SC_OPEN_MAX: c_int = 4;
+        pub const SC_VERSION: c_int = 29;
+        pub const SC_PAGESIZE: c_int = 30;
+        pub const PC_PATH_MAX: c_int = 4;
+        pub const RLIMIT_STACK: c_int = 3;
+        // mips 和 sparc 沿用了各自旧系统的编号
+        #[cfg(any(target_arch = "mips", target_arch = "mips64"))]
+        pub const RLIMIT: (c_int, c_int, c_int) = (5, 8, 6);
+        #[cfg(any(target_arch = "sparc", target_arch = "sparc64"))]
+        pub const RLIMIT: (c_int, c_int, c_int) = (6, 7, 9);
+        /// (RLIMIT_NOFILE, RLIMIT_NPROC, RLIMIT_AS)
+        #[cfg(not(any(target_arch = "mips", target_arch = "mips64", target_arch = "sparc", target_arch = "sparc64")))]
+        pub const RLIMIT: (c_int, c_int, c_int) = (7, 6, 9);
+        pub const RLIM_INFINITY: super::RlimT = !0;
+    }
+
+    // bionic 的 sysconf / pathconf 编号与 glibc 不同；资源编号来自内核，与 Linux 相同
+    #[cfg(target_os = "android")]
+    mod consts {
+        use std::ffi::c_int;
+
+        pub const SC_ARG_MAX: c_int = 0x00;
+        pub const SC_CLK_TCK: c_int = 0x06;
+        pub const SC_OPEN_MAX: c_int = 0x0b;
+        pub const SC_VERSION: c_int = 0x19;
+        pub const SC_PAGESIZE: c_int = 0x27;
+        pub const PC_PATH_MAX: c_int = 5;
+        pub const RLIMIT_STACK: c_int = 3;
+        /// (RLIMIT_NOFILE, RLIMIT_NPROC, RLIMIT_AS)
+        pub const RLIMIT: (c_int, c_int, c_int) = (7, 6, 9);
+        pub const RLIM_INFINITY: super::RlimT = !0;
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))]
+    mod consts {
+        use std::ffi::c_int;
+
+        pub const SC_ARG_MAX: c_int = 1;
+        pub const SC_CLK_TCK: c_int = 3;
+        pub const SC_OPEN_MAX: c_int = 5;
+        pub const SC_VERSION: c_int = 8;
+        #[cfg(target_os = "freebsd")]
+        pub const SC_PAGESIZE: c_int = 47;
+        #[cfg(not(target_os = "freebsd"))]
+        pub const SC_PAGESIZE: c_int = 29;
+        pub const PC_PATH_MAX: c_int = 5;
+        pub const RLIMIT_STACK: c_int = 3;
+        /// (RLIMIT_NOFILE, RLIMIT_NPROC, RLIMIT_AS)
+        #[cfg(target_os = "freebsd")]
+        pub const RLIMIT: (c_int, c_int, c_int) = (8, 7, 10);
+        #[cfg(not(target_os = "freebsd"))]
+        pub const RLIMIT: (c_int, c_int, c_int) = (8, 7, 5);
+        pub const RLIM_INFINITY: super::RlimT = i64::MAX as u64;
+    }
+
+    pub use consts::SC_VERSION;
+    use consts::*;
+
+    /// sysconf 和 pathconf 返回 -1 表示没有确定的限制（参数都是合法的常量，不会出错）
+    fn value(raw: c_long) -> LimitValue {
+        if raw < 0 {
+            LimitValue::Unlimited
+        } else {
+            LimitValue::Finite(raw as u64)
+        }
+    }
+
+    pub fn sysconf(name: c_int) -> LimitValue {
+        value(unsafe { c_sysconf(name) })
+    }
+
+    pub fn limit(limit: SysLimit) -> LimitValue {
+        match limit {
+            SysLimit::OpenFiles => sysconf(SC_OPEN_MAX),
+            SysLimit::PageSize => sysconf(SC_PAGESIZE),
+            SysLimit::ArgMax => sysconf(SC_ARG_MAX),
+            SysLimit::ClockTicks => sysconf(SC_CLK_TCK),
+            SysLimit::PathMax => value(unsafe { pathconf(c"/".as_ptr(), PC_PATH_MAX) }),
+        }
+    }
+
+    pub fn rlimit(resource: Resource) -> Option<Rlimit> {
+        let (nofile, nproc, address_space) = RLIMIT;
+        let number = match resource {
+            Resource::OpenFiles => nofile,
+            Resource::Stack => RLIMIT_STACK,
+            Resource::Processes => nproc,
+            Resource::AddressSpace => address_space,
+        };
+        let mut raw = RawRlimit { cur: 0, max: 0 };
+        if unsafe { getrlimit(number, &mut raw) } != 0 {
+            return None;
+        }
+        #[allow(clippy::unnecessary_cast)] // 只在 64 位目标上 RlimT 就是 u64
+        let value = |raw: RlimT| {
+            if raw == RLIM_INFINITY {
+                LimitValue::Unlimited
+            } else {
+                LimitValue::Finite(raw as u64)
+            }
+        };
+        Some(Rlimit { soft: value(raw.cur), hard: value(raw.max) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> PosixReport {
+        PosixReport {
+            version: Some(200809),
+            limits: vec![(SysLimit::PageSize, LimitValue::Finite(4096)), (SysLimit::PathMax, LimitValue::Unlimited)],
+            resources: vec![
+                (Resource::OpenFiles, Rlimit { soft: LimitValue::Finite(1024), hard: LimitValue::Finite(524288) }),
+                (Resource::Stack, Rlimit { soft: LimitValue::Finite(8 << 20), hard: LimitValue::Finite(8 << 20) }),
+            ],
+        }
+    }
+
+    #[test]
+    fn check_flags_limits_below_the_minimum() {
+        let report = sample();
+        assert_eq!(report.edition(), Some("POSIX.1-2008"));
+        assert!(report
+            .check(&[
+                Requirement::Version(200112),
+                Requirement::Limit(SysLimit::PageSize, 4096),
+                Requirement::Limit(SysLimit::PathMax, 1 << 20),
+                Requirement::Resource(Resource::OpenFiles, 1024),
+            ])
+            .is_empty());
+
+        let shortfalls = report.check(&[
+            Requirement::Version(202405),
+            Requirement::Resource(Resource::OpenFiles, 65536),
+            Requirement::Resource(Resource::Stack, 64 << 20),
+            Requirement::Limit(SysLimit::ArgMax, 1),
+        ]);
+        let actual: Vec<_> = shortfalls.iter().map(|s| (s.actual, s.raisable)).collect();
+        assert_eq!(actual, [(Some(200809), false), (Some(1024), true), (Some(8 << 20), false), (None, false)]);
+        assert_eq!(
+            shortfalls[1].to_string(),
+            "requires RLIMIT_NOFILE >= 65536, found 1024 (the hard limit allows raising it)"
+        );
+        assert_eq!(shortfalls[3].to_string(), "requires _SC_ARG_MAX >= 1, but it could not be determined");
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    #[test]
+    fn detect_reads_the_current_system() {
+        let report = PosixReport::detect();
+        assert!(report.version.is_some_and(|version| version >= 198808));
+        assert!(matches!(report.limit(SysLimit::PageSize), Some(LimitValue::Finite(size)) if size.is_power_of_two()));
+        assert_eq!(report.limit(SysLimit::ClockTicks), Some(LimitValue::Finite(100)));
+        let open_files = report.resource(Resource::OpenFiles).unwrap();
+        assert!(open_files.soft.at_least(3));
+        assert!(open_files.hard.at_least(match open_files.soft {
+            LimitValue::Finite(soft) => soft,
+            LimitValue::Unlimited => u64::MAX,
+        }));
+    }
+}
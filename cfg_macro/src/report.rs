@@ -0,0 +1,683 @@
+/*!
+ * 能力报告：JSON / TOML 导出与解析
+ *
+ * greet() 的输出是给人看的，部署脚本需要的是稳定、可解析的格式。
+ * 报告包含平台、架构和全部能力，两种格式的结构相同：
+ *
+ * ```json
+ * {
+ *   "platform": "linux",
+ *   "architecture": "x86_64",
+ *   "capabilities": {
+ *     "kind": "linux",
+ *     "epoll": true,
+ *     ...
+ *   }
+ * }
+ * ```
+ *
+ * ```toml
+ * platform = "linux"
+ * architecture = "x86_64"
+ *
+ * [capabilities]
+ * kind = "linux"
+ * epoll = true
+ * ...
+ * ```
+ *
+ * 这个 crate 不依赖任何第三方 crate，因此这里只实现报告用到的 JSON / TOML 子集，
+ * 解析时会检查能力是否齐全，缺项或多出未知能力都会报错，
+ * 避免脚本因为字段拼错而把某项能力当成"不支持"。
+ *
+ * 命令行：`cargo run -- --report json` 或 `cargo run -- --report toml`
+ */
+
+use crate::{CrossPlatformCode, Feature, PlatformCapabilities};
+use std::fmt;
+use std::str::FromStr;
+
+/// 报告格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Json,
+    Toml,
+}
+
+impl FromStr for ReportFormat {
+    type Err = ReportError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(ReportFormat::Json),
+            "toml" => Ok(ReportFormat::Toml),
+            other => Err(ReportError::new(format!(
+                "unknown report format `{}`; expected `json` or `toml`",
+                other
+            ))),
+        }
+    }
+}
+
+/// 解析报告失败的原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportError {
+    message: String,
+}
+
+impl ReportError {
+    fn new(message: impl Into<String>) -> Self {
+        Self { message: message.into() }
+    }
+
+    /// 带行号的错误，行号从 1 开始
+    fn at_line(line: usize, message: impl fmt::Display) -> Self {
+        Self::new(format!("line {}: {}", line, message))
+    }
+}
+
+impl fmt::Display for ReportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ReportError {}
+
+impl PlatformCapabilities {
+    /// 按 Feature::ALL 的顺序列出本平台所有能力及其是否可用
+    pub fn flags(&self) -> Vec<(&'static str, bool)> {
+        Feature::for_os(self.kind())
+            .filter_map(|feature| Some((feature.name(), self.get(feature)?)))
+            .collect()
+    }
+
+    /// 根据平台种类和能力表重建，缺少任何一项或者出现未知的能力都会报错
+    pub fn from_flags(kind: &str, flags: &[(String, bool)]) -> Result<Self, ReportError> {
+        let get = |feature: Feature| {
+            flags
+                .iter()
+                .find(|(key, _)| key == feature.name())
+                .map(|(_, value)| *value)
+                .ok_or_else(|| ReportError::new(format!("missing capability `{}` for {}", feature, kind)))
+        };
+        let capabilities = match kind {
+            "linux" => PlatformCapabilities::Linux {
+                epoll: get(Feature::Epoll)?,
+                signals: get(Feature::Signals)?,
+                unix_sockets: get(Feature::UnixSockets)?,
+                io_uring: get(Feature::IoUring)?,
+                inotify: get(Feature::Inotify)?,
+                seccomp: get(Feature::Seccomp)?,
+                landlock: get(Feature::Landlock)?,
+                cgroup_v2: get(Feature::CgroupV2)?,
+            },
+            "macos" => PlatformCapabilities::MacOS {
+                metal: get(Feature::Metal)?,
+                gcd: get(Feature::Gcd)?,
+                core_foundation: get(Feature::CoreFoundation)?,
+            },
+            "windows" => PlatformCapabilities::Windows {
+                win32: get(Feature::Win32)?,
+                com: get(Feature::Com)?,
+                registry: get(Feature::Registry)?,
+            },
+            other => {
+                return Err(ReportError::new(format!(
+                    "unknown capability kind `{}`; expected `linux`, `macos` or `windows`",
+                    other
+                )))
+            }
+        };
+        let known = |name: &str| name.parse().is_ok_and(|feature: Feature| feature.os() == kind);
+        if let Some((name, _)) = flags.iter().find(|(name, _)| !known(name)) {
+            return Err(ReportError::new(format!("unknown capability `{}` for {}", name, kind)));
+        }
+        Ok(capabilities)
+    }
+}
+
+impl CrossPlatformCode {
+    /// 按指定格式生成报告
+    pub fn report(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Json => self.to_json(),
+            ReportFormat::Toml => self.to_toml(),
+        }
+    }
+
+    /// 解析指定格式的报告
+    pub fn parse_report(text: &str, format: ReportFormat) -> Result<Self, ReportError> {
+        match format {
+            ReportFormat::Json => Self::from_json(text),
+            ReportFormat::Toml => Self::from_toml(text),
+        }
+    }
+
+    /// 生成 JSON 报告（两个空格缩进，以换行结尾）
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{\n");
+        out.push_str(&format!("  \"platform\": {},\n", json_string(self.platform())));
+        out.push_str(&format!("  \"architecture\": {},\n", json_string(self.architecture())));
+        out.push_str("  \"capabilities\": {\n");
+        out.push_str(&format!("    \"kind\": {}", json_string(self.capabilities().kind())));
+        for (name, value) in self.capabilities().flags() {
+            out.push_str(&format!(",\n    {}: {}", json_string(name), value));
+        }
+        out.push_str("\n  }\n}\n");
+        out
+    }
+
+    /// 生成 TOML 报告
+    pub fn to_toml(&self) -> String {
+        let mut out = String::new();
+        out.push_str(&format!("platform = {}\n", json_string(self.platform())));
+        out.push_str(&format!("architecture = {}\n", json_string(self.architecture())));
+        out.push_str("\n[capabilities]\n");
+        out.push_str(&format!("kind = {}\n", json_string(self.capabilities().kind())));
+        for (name, value) in self.capabilities().flags() {
+            out.push_str(&format!("{} = {}\n", name, value));
+        }
+        out
+    }
+
+    /// 解析 JSON 报告
+    pub fn from_json(text: &str) -> Result<Self, ReportError> {
+        let root = JsonParser::new(text).parse_document()?;
+        let root = root.as_object("report")?;
+        let platform = json_field(root, "platform")?.as_str("platform")?.to_string();
+        let architecture = json_field(root, "architecture")?.as_str("architecture")?.to_string();
+        let caps = json_field(root, "capabilities")?.as_object("capabilities")?;
+        let kind = json_field(caps, "kind")?.as_str("capabilities.kind")?;
+        let mut flags = Vec::new();
+        for (name, value) in caps.iter().filter(|(name, _)| name != "kind") {
+            flags.push((name.clone(), value.as_bool(name)?));
+        }
+        let capabilities = PlatformCapabilities::from_flags(kind, &flags)?;
+        Ok(Self::with_capabilities(platform, architecture, capabilities))
+    }
+
+    /// 解析 TOML 报告
+    pub fn from_toml(text: &str) -> Result<Self, ReportError> {
+        let tables = parse_toml(text)?;
+        let root = toml_table(&tables, "")?;
+        let caps = toml_table(&tables, "capabilities")?;
+        let platform = toml_field(root, "", "platform")?.as_str("platform")?.to_string();
+        let architecture = toml_field(root, "", "architecture")?.as_str("architecture")?.to_string();
+        let kind = toml_field(caps, "capabilities", "kind")?.as_str("capabilities.kind")?;
+        let mut flags = Vec::new();
+        for (name, value) in caps.iter().filter(|(name, _)| name != "kind") {
+            flags.push((name.clone(), value.as_bool(name)?));
+        }
+        let capabilities = PlatformCapabilities::from_flags(kind, &flags)?;
+        Ok(Self::with_capabilities(platform, architecture, capabilities))
+    }
+}
+
+/// 报告中出现的值：JSON 和 TOML 共用
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Value::Null => "null",
+            Value::Bool(_) => "a boolean",
+            Value::Number(_) => "a number",
+            Value::String(_) => "a string",
+            Value::Array(_) => "an array",
+            Value::Object(_) => "an object",
+        }
+    }
+
+    fn expected(&self, what: &str, expected: &str) -> ReportError {
+        ReportError::new(format!("`{}` must be {}, found {}", what, expected, self.type_name()))
+    }
+
+    fn as_str(&self, what: &str) -> Result<&str, ReportError> {
+        match self {
+            Value::String(s) => Ok(s),
+            other => Err(other.expected(what, "a string")),
+        }
+    }
+
+    fn as_bool(&self, what: &str) -> Result<bool, ReportError> {
+        match self {
+            Value::Bool(b) => Ok(*b),
+            other => Err(other.expected(what, "a boolean")),
+        }
+    }
+
+    fn as_object(&self, what: &str) -> Result<&[(String, Value)], ReportError> {
+        match self {
+            Value::Object(fields) => Ok(fields),
+            other => Err(other.expected(what, "an object")),
+        }
+    }
+}
+
+fn json_field<'a>(fields: &'a [(String, Value)], name: &str) -> Result<&'a Value, ReportError> {
+    fields
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value)
+        .ok_or_else(|| ReportError::new(format!("missing field `{}`", name)))
+}
+
+/// 带引号和转义的字符串字面量；TOML 的基本字符串与 JSON 字符串的转义规则兼容
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// 递归下降的 JSON 解析器，错误信息带行号
+struct JsonParser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    fn new(src: &'a str) -> Self {
+        Self { src, pos: 0 }
+    }
+
+    fn error(&self, message: impl fmt::Display) -> ReportError {
+        let line = self.src[..self.pos].matches('\n').count() + 1;
+        ReportError::at_line(line, message)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ReportError> {
+        self.eat(expected).map_err(|message| self.error(message))
+    }
+
+    /// 与 expect 相同，但错误信息不带行号，供 TOML 解析复用
+    fn eat(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.bump() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(format!("expected `{}`, found `{}`", expected, c)),
+            None => Err(format!("expected `{}`, found end of input", expected)),
+        }
+    }
+
+    fn parse_document(mut self) -> Result<Value, ReportError> {
+        let value = self.parse_value()?;
+        self.skip_whitespace();
+        if self.pos < self.src.len() {
+            return Err(self.error("trailing characters after JSON value"));
+        }
+        Ok(value)
+    }
+
+    fn parse_value(&mut self) -> Result<Value, ReportError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => self.parse_object(),
+            Some('[') => self.parse_array(),
+            Some('"') => self.parse_string().map(Value::String),
+            Some('t') => self.parse_keyword("true", Value::Bool(true)),
+            Some('f') => self.parse_keyword("false", Value::Bool(false)),
+            Some('n') => self.parse_keyword("null", Value::Null),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.parse_number(),
+            Some(c) => Err(self.error(format!("unexpected character `{}`", c))),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn parse_keyword(&mut self, keyword: &str, value: Value) -> Result<Value, ReportError> {
+        if self.src[self.pos..].starts_with(keyword) {
+            self.pos += keyword.len();
+            Ok(value)
+        } else {
+            Err(self.error("invalid literal"))
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Value, ReportError> {
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')) {
+            self.pos += 1;
+        }
+        self.src[start..self.pos]
+            .parse()
+            .map(Value::Number)
+            .map_err(|_| self.error(format!("invalid number `{}`", &self.src[start..self.pos])))
+    }
+
+    fn parse_string(&mut self) -> Result<String, ReportError> {
+        self.read_string().map_err(|message| self.error(message))
+    }
+
+    /// 解析字符串；错误信息不带行号，由调用方决定如何标注位置
+    fn read_string(&mut self) -> Result<String, String> {
+        self.eat('"')?;
+        let mut out = String::new();
+        loop {
+            match self.bump() {
+                Some('"') => return Ok(out),
+                Some('\\') => {
+                    let c = match self.bump() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => self.read_unicode_escape()?,
+                        _ => return Err("invalid escape sequence".to_string()),
+                    };
+                    out.push(c);
+                }
+                Some(c) if (c as u32) < 0x20 => return Err("control character in string".to_string()),
+                Some(c) => out.push(c),
+                None => return Err("unterminated string".to_string()),
+            }
+        }
+    }
+
+    /// `\uXXXX`，包括用代理对表示的 BMP 之外的字符
+    fn read_unicode_escape(&mut self) -> Result<char, String> {
+        let high = self.read_hex4()?;
+        let code = if (0xD800..0xDC00).contains(&high) {
+            if !self.src[self.pos..].starts_with("\\u") {
+                return Err("unpaired surrogate in string".to_string());
+            }
+            self.pos += 2;
+            let low = self.read_hex4()?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return Err("unpaired surrogate in string".to_string());
+            }
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+            high
+        };
+        char::from_u32(code).ok_or_else(|| "invalid unicode escape".to_string())
+    }
+
+    /// 恰好 4 个十六进制数字；from_str_radix 还会接受开头的 `+`，所以先逐个检查
+    fn read_hex4(&mut self) -> Result<u32, String> {
+        let digits = self
+            .src
+            .get(self.pos..self.pos + 4)
+            .filter(|digits| digits.bytes().all(|b| b.is_ascii_hexdigit()))
+            .ok_or("invalid unicode escape")?;
+        let code = u32::from_str_radix(digits, 16).map_err(|_| "invalid unicode escape")?;
+        self.pos += 4;
+        Ok(code)
+    }
+
+    fn parse_array(&mut self) -> Result<Value, ReportError> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.pos += 1;
+            return Ok(Value::Array(items));
+        }
+        loop {
+            items.push(self.parse_value()?);
+            self.skip_whitespace();
+            match self.bump() {
+                Some(',') => continue,
+                Some(']') => return Ok(Value::Array(items)),
+                _ => return Err(self.error("expected `,` or `]` in array")),
+            }
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Value, ReportError> {
+        self.expect('{')?;
+        let mut fields: Vec<(String, Value)> = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return Ok(Value::Object(fields));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.parse_string()?;
+            if fields.iter().any(|(k, _)| *k == key) {
+                return Err(self.error(format!("duplicate key `{}`", key)));
+            }
+            self.expect(':')?;
+            let value = self.parse_value()?;
+            fields.push((key, value));
+            self.skip_whitespace();
+            match self.bump() {
+                Some(',') => continue,
+                Some('}') => return Ok(Value::Object(fields)),
+                _ => return Err(self.error("expected `,` or `}` in object")),
+            }
+        }
+    }
+}
+
+/// (表名, 键值对)
+type Table = (String, Vec<(String, Value)>);
+
+/// TOML 子集：顶层键值对、`[table]` 表头、基本字符串与字面量字符串、布尔值、整数和注释
+///
+/// 返回所有的表，顶层的表名为空字符串
+fn parse_toml(text: &str) -> Result<Vec<Table>, ReportError> {
+    let mut tables: Vec<Table> = vec![(String::new(), Vec::new())];
+    for (index, raw) in text.lines().enumerate() {
+        let line_no = index + 1;
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(header) = line.strip_prefix('[') {
+            if header.starts_with('[') {
+                return Err(ReportError::at_line(line_no, "arrays of tables are not supported"));
+            }
+            let (name, rest) = header
+                .split_once(']')
+                .ok_or_else(|| ReportError::at_line(line_no, "unterminated table header"))?;
+            let rest = rest.trim();
+            if !rest.is_empty() && !rest.starts_with('#') {
+                return Err(ReportError::at_line(line_no, "unexpected characters after table header"));
+            }
+            let name = name.trim().to_string();
+            if tables.iter().any(|(existing, _)| *existing == name) {
+                return Err(ReportError::at_line(line_no, format!("duplicate table `[{}]`", name)));
+            }
+            tables.push((name, Vec::new()));
+            continue;
+        }
+
+        let (key, rest) = line
+            .split_once('=')
+            .ok_or_else(|| ReportError::at_line(line_no, "expected `key = value`"))?;
+        let key = toml_key(key.trim()).map_err(|msg| ReportError::at_line(line_no, msg))?;
+        let (value, rest) = toml_value(rest.trim()).map_err(|msg| ReportError::at_line(line_no, msg))?;
+        let rest = rest.trim();
+        if !rest.is_empty() && !rest.starts_with('#') {
+            return Err(ReportError::at_line(line_no, "unexpected characters after value"));
+        }
+        let (_, entries) = tables.last_mut().expect("the root table always exists");
+        if entries.iter().any(|(k, _)| *k == key) {
+            return Err(ReportError::at_line(line_no, format!("duplicate key `{}`", key)));
+        }
+        entries.push((key, value));
+    }
+    Ok(tables)
+}
+
+/// 裸键或带引号的键
+fn toml_key(key: &str) -> Result<String, String> {
+    if key.starts_with('"') || key.starts_with('\'') {
+        return match toml_value(key)? {
+            (Value::String(s), "") => Ok(s),
+            _ => Err(format!("invalid key `{}`", key)),
+        };
+    }
+    if !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        Ok(key.to_string())
+    } else {
+        Err(format!("invalid key `{}`", key))
+    }
+}
+
+/// 解析一个值，返回值和剩余的文本
+fn toml_value(text: &str) -> Result<(Value, &str), String> {
+    if let Some(rest) = text.strip_prefix("true") {
+        return Ok((Value::Bool(true), rest));
+    }
+    if let Some(rest) = text.strip_prefix("false") {
+        return Ok((Value::Bool(false), rest));
+    }
+    if let Some(body) = text.strip_prefix('\'') {
+        let end = body.find('\'').ok_or("unterminated string")?;
+        return Ok((Value::String(body[..end].to_string()), &body[end + 1..]));
+    }
+    if text.starts_with('"') {
+        // 基本字符串的转义规则是 JSON 的子集，直接交给 JSON 解析器
+        let mut parser = JsonParser::new(text);
+        let s = parser.read_string()?;
+        return Ok((Value::String(s), &text[parser.pos..]));
+    }
+    let end = text.find(|c: char| c.is_whitespace() || c == '#').unwrap_or(text.len());
+    let number = text[..end].replace('_', "");
+    match number.parse::<i64>() {
+        Ok(n) => Ok((Value::Number(n as f64), &text[end..])),
+        Err(_) if text.is_empty() => Err("missing value".to_string()),
+        Err(_) => Err(format!("unsupported value `{}`", &text[..end])),
+    }
+}
+
+fn toml_table<'a>(
+    tables: &'a [Table],
+    name: &str,
+) -> Result<&'a [(String, Value)], ReportError> {
+    tables
+        .iter()
+        .find(|(table, _)| table == name)
+        .map(|(_, entries)| entries.as_slice())
+        .ok_or_else(|| ReportError::new(format!("missing table `[{}]`", name)))
+}
+
+fn toml_field<'a>(entries: &'a [(String, Value)], table: &str, name: &str) -> Result<&'a Value, ReportError> {
+    entries
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value)
+        .ok_or_else(|| match table {
+            "" => ReportError::new(format!("missing key `{}`", name)),
+            _ => ReportError::new(format!("missing key `{}` in `[{}]`", name, table)),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn linux() -> CrossPlatformCode {
+        CrossPlatformCode::with_capabilities(
+            "linux",
+            "x86_64",
+            PlatformCapabilities::Linux {
+                epoll: true,
+                signals: true,
+                unix_sockets: true,
+                io_uring: false,
+                inotify: true,
+                seccomp: true,
+                landlock: false,
+                cgroup_v2: true,
+            },
+        )
+    }
+
+    #[test]
+    fn round_trips_through_both_formats() {
+        let code = linux();
+        for format in [ReportFormat::Json, ReportFormat::Toml] {
+            let parsed = CrossPlatformCode::parse_report(&code.report(format), format).unwrap();
+            assert_eq!(parsed.platform(), "linux");
+            assert_eq!(parsed.architecture(), "x86_64");
+            assert_eq!(parsed.capabilities(), code.capabilities());
+        }
+    }
+
+    #[test]
+    fn rejects_missing_and_unknown_capabilities() {
+        let json = linux().to_json().replace("\"epoll\"", "\"epol\"");
+        let err = CrossPlatformCode::from_json(&json).err().unwrap();
+        assert_eq!(err.to_string(), "missing capability `epoll` for linux");
+
+        let toml = linux().to_toml() + "metal = true\n";
+        let err = CrossPlatformCode::from_toml(&toml).err().unwrap();
+        assert_eq!(err.to_string(), "unknown capability `metal` for linux");
+    }
+
+    #[test]
+    fn toml_subset_handles_comments_and_quoting() {
+        let text = "# 报告\nplatform = 'macos' # 注释\narchitecture = \"a#b\"\n\n[capabilities]\nkind = \"macos\"\nmetal = true\ngcd = false\ncore_foundation = true\n";
+        let code = CrossPlatformCode::from_toml(text).unwrap();
+        assert_eq!(code.architecture(), "a#b");
+        assert_eq!(
+            *code.capabilities(),
+            PlatformCapabilities::MacOS { metal: true, gcd: false, core_foundation: true }
+        );
+
+        let err = CrossPlatformCode::from_toml("platform = \"x\"\nplatform = \"y\"\n").err().unwrap();
+        assert_eq!(err.to_string(), "line 2: duplicate key `platform`");
+    }
+
+    #[test]
+    fn string_errors_carry_exactly_one_line_number() {
+        let err = CrossPlatformCode::from_toml("platform = \"\\q\"\n").err().unwrap();
+        assert_eq!(err.to_string(), "line 1: invalid escape sequence");
+        let err = CrossPlatformCode::from_toml("# 报告\n\nplatform = \"\\u00\"\n").err().unwrap();
+        assert_eq!(err.to_string(), "line 3: invalid unicode escape");
+
+        let err = JsonParser::new("{\n\"a\": \"\\q\"}").parse_document().err().unwrap();
+        assert_eq!(err.to_string(), "line 2: invalid escape sequence");
+    }
+
+    #[test]
+    fn unicode_escapes_need_four_hex_digits() {
+        let parse = |text: &str| JsonParser::new(text).parse_document();
+        assert_eq!(parse("\"\\u0041\\ud83d\\ude00\""), Ok(Value::String("A😀".to_string())));
+        assert_eq!(parse("\"\\u+041\"").err().unwrap().to_string(), "line 1: invalid unicode escape");
+        assert!(parse("\"\\u-041\"").is_err());
+    }
+}
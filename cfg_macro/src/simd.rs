@@ -0,0 +1,280 @@
+/*!
+ * 运行时 SIMD 指令集检测与函数分派
+ *
+ * `#[cfg(target_feature = "avx2")]` 只看编译时打开了哪些指令集，默认的 x86_64 目标只保证 SSE2，
+ * 因此发行的二进制文件里这类条件几乎总是假的。更常见的做法是同时编译多份实现，
+ * 运行时检测 CPU 实际支持的指令集，再选出最快的一份：
+ *
+ * ```rust
+ * use cfg_macro::simd::{Dispatch, SimdFeature};
+ *
+ * // 带 #[target_feature] 的实现只能是 unsafe fn，所有实现统一登记为 unsafe 函数指针
+ * type Sum = unsafe fn(&[f32]) -> f32;
+ *
+ * fn sum_scalar(values: &[f32]) -> f32 {
+ *     values.iter().sum()
+ * }
+ *
+ * #[cfg(target_arch = "x86_64")]
+ * #[target_feature(enable = "avx2")]
+ * unsafe fn sum_avx2(values: &[f32]) -> f32 {
+ *     values.iter().sum()
+ * }
+ *
+ * let dispatch = Dispatch::new(sum_scalar as Sum);
+ * #[cfg(target_arch = "x86_64")]
+ * let dispatch = dispatch.with(SimdFeature::Avx2, sum_avx2 as Sum);
+ *
+ * let sum = dispatch.select();
+ * // SAFETY: select 只会选中当前 CPU 支持的指令集对应的实现
+ * assert_eq!(unsafe { sum(&[1.0, 2.0, 3.0]) }, 6.0);
+ * ```
+ */
+
+use std::fmt;
+
+/// 关心的 SIMD 指令集
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SimdFeature {
+    /// x86 的 SSE4.2（128 位）
+    Sse42,
+    /// x86 的 AVX2（256 位）
+    Avx2,
+    /// x86 的 AVX-512 基础指令 AVX-512F（512 位）
+    Avx512,
+    /// ARM 的 NEON / Advanced SIMD（128 位）
+    Neon,
+}
+
+impl SimdFeature {
+    /// 全部指令集，按向量宽度从窄到宽排列
+    pub const ALL: [SimdFeature; 4] = [SimdFeature::Sse42, SimdFeature::Neon, SimdFeature::Avx2, SimdFeature::Avx512];
+
+    /// 对应的 target_feature 名字，可以直接用在 `#[target_feature(enable = "...")]` 中
+    pub fn name(self) -> &'static str {
+        match self {
+            SimdFeature::Sse42 => "sse4.2",
+            SimdFeature::Avx2 => "avx2",
+            SimdFeature::Avx512 => "avx512f",
+            SimdFeature::Neon => "neon",
+        }
+    }
+
+    /// 通常的写法，例如 "AVX-512"
+    pub fn label(self) -> &'static str {
+        match self {
+            SimdFeature::Sse42 => "SSE4.2",
+            SimdFeature::Avx2 => "AVX2",
+            SimdFeature::Avx512 => "AVX-512",
+            SimdFeature::Neon => "NEON",
+        }
+    }
+
+    /// 向量寄存器宽度（位）
+    pub fn width(self) -> u32 {
+        match self {
+            SimdFeature::Sse42 | SimdFeature::Neon => 128,
+            SimdFeature::Avx2 => 256,
+            SimdFeature::Avx512 => 512,
+        }
+    }
+
+    /// 这个指令集对应的优化级别：128 位为 2，256 位为 3，512 位为 4
+    pub fn optimization_level(self) -> u8 {
+        match self.width() {
+            512 => 4,
+            256 => 3,
+            _ => 2,
+        }
+    }
+
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+
+    /// 当前 CPU 是否支持；不属于当前架构的指令集总是 false
+    pub fn is_detected(self) -> bool {
+        match self {
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            SimdFeature::Sse42 => std::arch::is_x86_feature_detected!("sse4.2"),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            SimdFeature::Avx2 => std::arch::is_x86_feature_detected!("avx2"),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            SimdFeature::Avx512 => std::arch::is_x86_feature_detected!("avx512f"),
+            #[cfg(target_arch = "aarch64")]
+            SimdFeature::Neon => std::arch::is_aarch64_feature_detected!("neon"),
+            #[allow(unreachable_patterns)] // 在 x86 和 aarch64 之外的架构上这是唯一的分支
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for SimdFeature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// 一组 SIMD 指令集
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SimdFeatures {
+    bits: u8,
+}
+
+impl SimdFeatures {
+    /// 空集合，表示只能使用标量代码
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// 检测当前 CPU 支持的指令集
+    ///
+    /// 标准库的检测宏会缓存结果，重复调用的开销很小
+    pub fn detect() -> Self {
+        SimdFeature::ALL.into_iter().filter(|feature| feature.is_detected()).collect()
+    }
+
+    /// 加入一个指令集
+    pub fn with(mut self, feature: SimdFeature) -> Self {
+        self.bits |= feature.bit();
+        self
+    }
+
+    pub fn contains(self, feature: SimdFeature) -> bool {
+        self.bits & feature.bit() != 0
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.bits == 0
+    }
+
+    /// 集合中的指令集，按向量宽度从窄到宽
+    pub fn iter(self) -> impl Iterator<Item = SimdFeature> {
+        SimdFeature::ALL.into_iter().filter(move |feature| self.contains(*feature))
+    }
+
+    /// 向量宽度最大的指令集；空集合为 None
+    pub fn best(self) -> Option<SimdFeature> {
+        self.iter().last()
+    }
+
+    /// 由指令集推导的优化级别
+    ///
+    /// 只能用标量代码时为 1，否则取最宽的指令集的级别：128 位（SSE4.2、NEON）为 2，
+    /// AVX2 为 3，AVX-512 为 4
+    pub fn optimization_level(self) -> u8 {
+        self.best().map_or(1, SimdFeature::optimization_level)
+    }
+}
+
+impl FromIterator<SimdFeature> for SimdFeatures {
+    fn from_iter<I: IntoIterator<Item = SimdFeature>>(iter: I) -> Self {
+        iter.into_iter().fold(Self::empty(), Self::with)
+    }
+}
+
+impl fmt::Display for SimdFeatures {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return f.write_str("none");
+        }
+        for (i, feature) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}", feature)?;
+        }
+        Ok(())
+    }
+}
+
+/// 按检测到的指令集选择函数实现
+///
+/// 每个实现登记它依赖的指令集；选择时在支持的实现中取向量宽度最大的一个，
+/// 都不支持时使用标量实现。`F` 通常是函数指针，也可以是任意值（例如闭包或 trait 对象）。
+#[derive(Debug, Clone)]
+pub struct Dispatch<F> {
+    fallback: F,
+    variants: Vec<(SimdFeature, F)>,
+}
+
+impl<F> Dispatch<F> {
+    /// 以标量实现为起点
+    pub fn new(fallback: F) -> Self {
+        Self { fallback, variants: Vec::new() }
+    }
+
+    /// 登记依赖 `feature` 的实现；同一个指令集登记多次时后登记的替换先登记的
+    pub fn with(mut self, feature: SimdFeature, implementation: F) -> Self {
+        match self.variants.iter_mut().find(|(existing, _)| *existing == feature) {
+            Some(slot) => slot.1 = implementation,
+            None => self.variants.push((feature, implementation)),
+        }
+        self
+    }
+
+    /// 按当前 CPU 选择：在检测到的指令集支持的实现中取向量宽度最大的一个
+    pub fn select(&self) -> &F {
+        // SAFETY: 集合来自 SimdFeatures::detect，其中的指令集当前 CPU 都支持
+        unsafe { self.select_for(SimdFeatures::detect()) }
+    }
+
+    /// 在 `features` 支持的实现中选出向量宽度最大的一个
+    ///
+    /// # Safety
+    ///
+    /// 选中的实现可能带有 `#[target_feature]`，`features` 中的指令集必须都是当前 CPU 支持的，
+    /// 即 [`SimdFeatures::detect`] 的子集。`F` 不会调用这类函数时（例如测试中的字符串）任意集合都可以
+    pub unsafe fn select_for(&self, features: SimdFeatures) -> &F {
+        self.variants
+            .iter()
+            .filter(|(feature, _)| features.contains(*feature))
+            .max_by_key(|(feature, _)| feature.width())
+            .map_or(&self.fallback, |(_, implementation)| implementation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn optimization_level_follows_the_widest_feature() {
+        assert_eq!(SimdFeatures::empty().optimization_level(), 1);
+        let neon: SimdFeatures = [SimdFeature::Neon].into_iter().collect();
+        assert_eq!(neon.optimization_level(), 2);
+        let x86 = SimdFeatures::empty().with(SimdFeature::Sse42).with(SimdFeature::Avx2);
+        assert_eq!((x86.best(), x86.optimization_level()), (Some(SimdFeature::Avx2), 3));
+        assert_eq!(x86.with(SimdFeature::Avx512).optimization_level(), 4);
+        assert_eq!(x86.to_string(), "SSE4.2, AVX2");
+    }
+
+    #[test]
+    fn detected_features_belong_to_the_current_architecture() {
+        let detected = SimdFeatures::detect();
+        for feature in detected.iter() {
+            let x86 = feature != SimdFeature::Neon;
+            assert_eq!(x86, cfg!(any(target_arch = "x86", target_arch = "x86_64")), "{}", feature);
+        }
+        // aarch64 的 NEON 是必备的
+        assert_eq!(detected.contains(SimdFeature::Neon), cfg!(target_arch = "aarch64"));
+    }
+
+    #[test]
+    fn dispatch_picks_the_widest_supported_variant() {
+        let dispatch = Dispatch::new("scalar")
+            .with(SimdFeature::Sse42, "sse")
+            .with(SimdFeature::Avx512, "avx512")
+            .with(SimdFeature::Avx2, "avx2-old")
+            .with(SimdFeature::Avx2, "avx2");
+
+        // SAFETY: 这里的实现只是字符串，任意集合都不会执行 target_feature 代码
+        let select = |features| unsafe { *dispatch.select_for(features) };
+        assert_eq!(select(SimdFeatures::empty()), "scalar");
+        assert_eq!(select(SimdFeatures::empty().with(SimdFeature::Neon)), "scalar");
+        let avx2 = SimdFeatures::empty().with(SimdFeature::Sse42).with(SimdFeature::Avx2);
+        assert_eq!(select(avx2), "avx2");
+        assert_eq!(select(avx2.with(SimdFeature::Avx512)), "avx512");
+        assert_eq!(*dispatch.select(), select(SimdFeatures::detect()));
+    }
+}
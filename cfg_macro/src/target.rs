@@ -0,0 +1,135 @@
+/*!
+ * 目标平台描述
+ *
+ * #[cfg(...)] 在编译期决定哪些代码存在，而编译出来的代码在运行时还会读取
+ * `std::env::consts::OS` 等信息来决定走哪个分支。后者可以被替换：
+ * TargetPlatform 把这些运行时信息收集到一起，默认取当前主机（host），
+ * 也可以构造一个模拟的平台，在 Linux 上驱动 macOS / Windows 分支的代码：
+ *
+ * ```rust
+ * # #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))] {
+ * use cfg_macro::{CrossPlatformCode, Feature, TargetPlatform};
+ *
+ * let mac = TargetPlatform::simulated("macos", "aarch64");
+ * assert_eq!((mac.pointer_width, mac.family.as_str()), (64, "unix"));
+ *
+ * let code = CrossPlatformCode::for_platform(&mac).unwrap();
+ * assert_eq!(code.supports(Feature::Metal), Ok(true));
+ * # }
+ * ```
+ *
+ * 模拟只影响运行时的分支：只在 macOS 上编译的类型（如 MacOSCode）在 Linux 上仍然不存在。
+ */
+
+use std::env;
+use std::fmt;
+
+/// 目标平台的运行时描述，字段取值与 `std::env::consts` 和对应的 cfg 键一致
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TargetPlatform {
+    /// 操作系统，对应 `target_os`，例如 "linux"、"macos"、"windows"
+    pub os: String,
+    /// 架构，对应 `target_arch`，例如 "x86_64"、"aarch64"
+    pub arch: String,
+    /// 指针宽度（位），对应 `target_pointer_width`
+    pub pointer_width: u32,
+    /// 系列家族，对应 `target_family`，例如 "unix"、"windows"；未知时为空字符串
+    pub family: String,
+    /// 是否由 [`TargetPlatform::simulated`] 构造；决定能否实际探测，不随字段取值变化
+    simulated: bool,
+}
+
+impl TargetPlatform {
+    /// 当前主机
+    pub fn host() -> Self {
+        let pointer_width = if cfg!(target_pointer_width = "64") {
+            64
+        } else if cfg!(target_pointer_width = "32") {
+            32
+        } else {
+            16
+        };
+        Self {
+            os: env::consts::OS.to_string(),
+            arch: env::consts::ARCH.to_string(),
+            pointer_width,
+            family: env::consts::FAMILY.to_string(),
+            simulated: false,
+        }
+    }
+
+    /// 模拟的平台，指针宽度和系列家族根据架构与操作系统推断
+    ///
+    /// 无法识别的架构按 64 位处理，无法识别的操作系统家族为空字符串；
+    /// 推断不对时可以直接修改对应的字段
+    pub fn simulated(os: &str, arch: &str) -> Self {
+        Self {
+            os: os.to_string(),
+            arch: arch.to_string(),
+            pointer_width: pointer_width_of(arch),
+            family: family_of(os).to_string(),
+            simulated: true,
+        }
+    }
+
+    /// 是否就是当前主机
+    ///
+    /// 只有当前主机上的能力可以实际探测，模拟平台只能给出名义上的能力。
+    /// 判断依据是构造方式而不是字段取值：即使模拟的 os、arch 与主机相同，也不会探测
+    pub fn is_host(&self) -> bool {
+        !self.simulated
+    }
+}
+
+impl Default for TargetPlatform {
+    fn default() -> Self {
+        Self::host()
+    }
+}
+
+impl fmt::Display for TargetPlatform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{} ({}-bit", self.arch, self.os, self.pointer_width)?;
+        if !self.family.is_empty() {
+            write!(f, ", {}", self.family)?;
+        }
+        f.write_str(")")
+    }
+}
+
+/// 常见架构的指针宽度
+fn pointer_width_of(arch: &str) -> u32 {
+    match arch {
+        "x86" | "arm" | "mips" | "powerpc" | "riscv32" | "sparc" | "wasm32" | "hexagon" | "csky" | "m68k" => 32,
+        "avr" | "msp430" => 16,
+        _ => 64,
+    }
+}
+
+/// 常见操作系统所属的系列家族
+fn family_of(os: &str) -> &'static str {
+    match os {
+        "windows" => "windows",
+        "linux" | "macos" | "ios" | "tvos" | "watchos" | "visionos" | "android" | "freebsd" | "netbsd"
+        | "openbsd" | "dragonfly" | "solaris" | "illumos" | "haiku" | "fuchsia" | "redox" | "aix" => "unix",
+        _ => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simulated_platforms_infer_width_and_family() {
+        let win32 = TargetPlatform::simulated("windows", "x86");
+        assert_eq!((win32.pointer_width, win32.family.as_str()), (32, "windows"));
+        assert_eq!(win32.to_string(), "x86-windows (32-bit, windows)");
+
+        let wasm = TargetPlatform::simulated("unknown", "wasm32");
+        assert_eq!(wasm.to_string(), "wasm32-unknown (32-bit)");
+
+        assert!(TargetPlatform::host().is_host());
+        assert!(!TargetPlatform::simulated(env::consts::OS, env::consts::ARCH).is_host());
+    }
+}
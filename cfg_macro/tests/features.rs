@@ -0,0 +1,55 @@
+// cargo feature 的每种组合都要测试：
+//   cargo test
+//   cargo test --features advanced-features
+//   cargo test --features custom-feature
+//   cargo test --all-features
+
+#[test]
+fn enabled_features_match_cfg() {
+    let expected: Vec<&str> = [
+        ("advanced-features", cfg!(feature = "advanced-features")),
+        ("custom-feature", cfg!(feature = "custom-feature")),
+    ]
+    .into_iter()
+    .filter(|(_, enabled)| *enabled)
+    .map(|(name, _)| name)
+    .collect();
+    assert_eq!(cfg_macro::enabled_features(), expected.as_slice());
+}
+
+#[cfg(feature = "advanced-features")]
+#[test]
+fn advanced_features_follow_build_mode() {
+    let advanced = cfg_macro::AdvancedFeatureCode::new();
+    assert_eq!(advanced.is_experimental_enabled(), cfg!(debug_assertions));
+    assert_eq!(advanced.is_debug_mode(), cfg!(debug_assertions));
+    assert!(advanced.has_beta_features());
+}
+
+#[cfg(feature = "custom-feature")]
+#[test]
+fn custom_probes_run_in_registration_order() {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    let calls = Rc::new(Cell::new(0));
+    let counter = Rc::clone(&calls);
+    let mut custom = cfg_macro::CustomFeatureCode::new();
+    custom
+        .register("yes", || true)
+        .register("no", || false)
+        .register("counted", move || {
+            counter.set(counter.get() + 1);
+            true
+        });
+
+    assert_eq!(custom.names().collect::<Vec<_>>(), ["yes", "no", "counted"]);
+    assert_eq!(custom.run(), [("yes", true), ("no", false), ("counted", true)]);
+    assert_eq!(custom.check("counted"), Some(true));
+    assert_eq!(calls.get(), 2, "每次查询都会重新执行探测");
+    assert_eq!(custom.check("missing"), None);
+
+    // 同名的探测被替换，位置移到最后
+    custom.register("yes", || false);
+    assert_eq!(custom.run(), [("no", false), ("counted", true), ("yes", false)]);
+}
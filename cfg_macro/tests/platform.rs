@@ -0,0 +1,129 @@
+// 与平台相关的公开 API：每个测试只在对应类型存在的目标上编译
+
+use cfg_macro::{Feature, FeatureError};
+
+#[test]
+fn feature_names_round_trip() {
+    for feature in Feature::ALL {
+        assert_eq!(feature.name().parse::<Feature>(), Ok(feature));
+        assert_eq!(feature.to_string(), feature.name());
+    }
+    assert_eq!("epol".parse::<Feature>(), Err(FeatureError::Unknown("epol".to_string())));
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+#[test]
+fn cross_platform_code_describes_the_current_target() {
+    use cfg_macro::CrossPlatformCode;
+
+    let code = CrossPlatformCode::new();
+    assert_eq!(code.platform(), std::env::consts::OS);
+    assert_eq!(code.architecture(), std::env::consts::ARCH);
+
+    // features() 覆盖当前平台的全部功能，且都能查询
+    let features: Vec<Feature> = code.features().map(|(feature, _)| feature).collect();
+    assert_eq!(features, Feature::current_platform().collect::<Vec<_>>());
+    for feature in features {
+        assert!(code.supports(feature).is_ok());
+    }
+
+    // 其他平台的功能得到 NotApplicable，而不是 false
+    let foreign = Feature::ALL.into_iter().find(|f| f.os() != std::env::consts::OS).unwrap();
+    assert!(matches!(
+        code.supports(foreign),
+        Err(FeatureError::NotApplicable { feature, .. }) if feature == foreign
+    ));
+
+    // 只有在 Linux 主机上探测时才有 seccomp 模式，且与能力表一致
+    let seccomp = code.supports(Feature::Seccomp).unwrap_or(false);
+    assert_eq!(code.seccomp_mode().is_some(), seccomp);
+    let simulated = CrossPlatformCode::with_capabilities("linux", "x86_64", *code.capabilities());
+    assert_eq!(simulated.seccomp_mode(), None);
+}
+
+#[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+#[test]
+fn thread_count_is_bounded() {
+    use cfg_macro::{Linux64Code, ThreadCountSource};
+
+    let code = Linux64Code::new();
+    assert!(code.processor_count() >= 1);
+
+    // 不修改环境变量：并行运行的其他测试可能同时在读取环境。
+    // 各项来源的优先级和环境变量的解析由 combinations 模块的单元测试覆盖
+    let count = code.thread_count();
+    if count.source != ThreadCountSource::EnvOverride {
+        assert!((1..=Linux64Code::MAX_THREADS).contains(&count.threads));
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+#[test]
+fn unix_like_code_lists_posix_features() {
+    let code = cfg_macro::UnixLikeCode::new();
+    // 只有声明了 sysconf 常量的系统才能查询 POSIX 版本
+    if cfg!(any(target_os = "linux", target_os = "macos", target_os = "freebsd")) {
+        assert!(code.is_posix_compliant());
+        assert!(code.posix().check(&[cfg_macro::Requirement::Version(200112)]).is_empty());
+    }
+    assert!(code.get_features().iter().any(|f| f == "POSIX 兼容"));
+    assert_eq!(*code.environment(), cfg_macro::RuntimeEnvironment::detect());
+
+    let docker = cfg_macro::RuntimeEnvironment {
+        container: Some(cfg_macro::Container::Docker),
+        ..Default::default()
+    };
+    let code = cfg_macro::UnixLikeCode::with_environment(docker);
+    assert!(code.in_container());
+    assert_eq!(code.environment().to_string(), "container=docker");
+}
+
+#[test]
+fn build_cfgs_match_the_compiler() {
+    let expected: Vec<&str> = [("has_io_uring", cfg!(has_io_uring)), ("has_statx", cfg!(has_statx))]
+        .into_iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(name, _)| name)
+        .collect();
+    assert_eq!(cfg_macro::build_cfgs(), expected.as_slice());
+}
+
+#[cfg(all(target_os = "linux", has_statx))]
+#[test]
+fn birth_time_of_a_new_file_is_recent_or_unrecorded() {
+    use std::time::{Duration, SystemTime};
+
+    let path = std::env::temp_dir().join(format!("cfg_macro_btime_{}", std::process::id()));
+    std::fs::write(&path, b"statx").unwrap();
+    let birth = cfg_macro::birth_time(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    // 文件系统不记录创建时间时为 None（例如某些 overlayfs / tmpfs 版本）
+    if let Some(birth) = birth.unwrap() {
+        let age = SystemTime::now().duration_since(birth).unwrap_or_default();
+        assert!(age < Duration::from_secs(60), "birth time {:?} is too old", birth);
+    }
+    assert!(cfg_macro::birth_time(std::path::Path::new("/nonexistent/cfg_macro")).is_err());
+}
+
+#[cfg(all(
+    unix,
+    not(target_os = "macos"),
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+#[test]
+fn specific_unix_code_derives_optimization_level_from_simd() {
+    use cfg_macro::{SimdFeature, SimdFeatures, SpecificUnixCode};
+
+    let scalar = SpecificUnixCode::with_simd(SimdFeatures::empty());
+    assert_eq!(scalar.optimization_level(), 1);
+    assert!(!scalar.supported_features().iter().any(|f| f.starts_with("SIMD")));
+
+    let avx2 = SpecificUnixCode::with_simd(SimdFeatures::empty().with(SimdFeature::Sse42).with(SimdFeature::Avx2));
+    assert_eq!(avx2.optimization_level(), 3);
+    assert!(avx2.supported_features().iter().any(|f| f == "SIMD: AVX2"));
+
+    let host = SpecificUnixCode::new();
+    assert_eq!(host.simd_features(), SimdFeatures::detect());
+    assert!(host.optimization_level() >= 1);
+}
@@ -0,0 +1,107 @@
+// Graph（Rc<RefCell<Node>>）与 ArenaGraph（arena + 代数句柄）的性能对比
+//
+// 两者的 API 相同，同一组操作各跑若干次，取最快的一次：
+//   cargo bench               默认 20000 个节点，约 8 万条边
+//   cargo bench -- 100000     指定节点数
+//
+// 输出的倍数是 Graph 的耗时除以 ArenaGraph 的耗时，大于 1 表示 arena 更快。
+
+use graph::{ArenaGraph, Graph};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const DEFAULT_NODES: usize = 20_000;
+const REPEATS: usize = 7;
+
+/// 确定性的伪随机边：每个节点向后面 16 个节点中的 4 个连边，得到无环图
+fn edges(nodes: usize) -> Vec<(usize, usize)> {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut edges = Vec::with_capacity(nodes * 4);
+    for from in 0..nodes {
+        for _ in 0..4 {
+            // xorshift64
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let to = from + 1 + (state % 16) as usize;
+            if to < nodes {
+                edges.push((from, to));
+            }
+        }
+    }
+    edges
+}
+
+/// 运行 `REPEATS` 次，返回最短的一次；`setup` 的耗时不计入
+fn measure<S, R>(mut setup: impl FnMut() -> S, mut run: impl FnMut(S) -> R) -> Duration {
+    (0..REPEATS)
+        .map(|_| {
+            let input = setup();
+            let start = Instant::now();
+            black_box(run(input));
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+/// 对一种图类型生成整套测量；两种图的方法名相同，用宏避免写两遍
+macro_rules! suite {
+    ($name:ident, $graph:ty) => {
+        fn $name(nodes: usize, edges: &[(usize, usize)]) -> Vec<(&'static str, Duration)> {
+            let build = || {
+                let mut g = <$graph>::new();
+                let ids: Vec<_> = (0..nodes).map(|i| g.add_node(i as u64)).collect();
+                for &(from, to) in edges {
+                    let _ = g.add_edge(ids[from], ids[to]);
+                }
+                (g, ids)
+            };
+            let (g, ids) = build();
+            let (first, last) = (ids[0], ids[nodes - 1]);
+            vec![
+                ("build", measure(|| (), |_| build().0.edge_count())),
+                ("bfs", measure(|| (), |_| g.bfs(first).count())),
+                ("dfs", measure(|| (), |_| g.dfs(first).count())),
+                ("topological_sort", measure(|| (), |_| g.topological_sort().map(|order| order.len()))),
+                ("shortest_path", measure(|| (), |_| g.shortest_path(first, last).map(|path| path.len()))),
+                ("sum values", measure(|| (), |_| g.node_ids().map(|id| *g.get(id).unwrap()).sum::<u64>())),
+                (
+                    "remove 1/3 + re-add",
+                    measure(build, |(mut g, ids)| {
+                        for &id in ids.iter().step_by(3) {
+                            g.remove_node(id);
+                        }
+                        for value in 0..ids.len().div_ceil(3) {
+                            g.add_node(value as u64);
+                        }
+                        g.node_count()
+                    }),
+                ),
+                ("drop", measure(build, |(g, _)| drop(g))),
+            ]
+        }
+    };
+}
+
+suite!(rc_suite, Graph<u64>);
+suite!(arena_suite, ArenaGraph<u64>);
+
+fn main() {
+    // cargo bench 会额外传入 --bench，只取第一个数字参数
+    let nodes = std::env::args()
+        .skip(1)
+        .find_map(|arg| arg.parse::<usize>().ok())
+        .filter(|&nodes| nodes > 0)
+        .unwrap_or(DEFAULT_NODES);
+    let edges = edges(nodes);
+    println!("{} nodes, {} edges, best of {} runs\n", nodes, edges.len(), REPEATS);
+
+    let rc = rc_suite(nodes, &edges);
+    let arena = arena_suite(nodes, &edges);
+    println!("{:<22}{:>14}{:>14}{:>10}", "workload", "Rc<RefCell>", "arena", "ratio");
+    for ((name, rc), (_, arena)) in rc.iter().zip(&arena) {
+        let ratio = rc.as_secs_f64() / arena.as_secs_f64().max(f64::MIN_POSITIVE);
+        println!("{:<22}{:>14.3?}{:>14.3?}{:>9.2}x", name, rc, arena, ratio);
+    }
+}
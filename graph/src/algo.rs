@@ -0,0 +1,119 @@
+/*!
+ * 两种实现共用的图算法
+ *
+ * 算法只需要知道有哪些节点、每个节点的后继和入度，通过 Adjacency 访问；
+ * 遍历迭代器则由各个实现自己提供，Graph 沿着 Rc 前进，ArenaGraph 按下标访问。
+ */
+
+use crate::error::GraphError;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+/// 图的邻接信息
+pub(crate) trait Adjacency {
+    type Id: Copy + Eq + Hash;
+
+    /// 所有节点，顺序决定了算法在并列时的选择
+    fn ids(&self) -> impl Iterator<Item = Self::Id> + '_;
+
+    fn contains(&self, id: Self::Id) -> bool;
+
+    /// 按添加边的顺序访问后继节点
+    fn for_each_neighbor(&self, id: Self::Id, f: impl FnMut(Self::Id));
+
+    fn in_degree(&self, id: Self::Id) -> usize;
+
+    fn neighbor_list(&self, id: Self::Id) -> Vec<Self::Id> {
+        let mut neighbors = Vec::new();
+        self.for_each_neighbor(id, |neighbor| neighbors.push(neighbor));
+        neighbors
+    }
+}
+
+/// Kahn 算法；入度相同时按 `ids()` 的顺序输出
+pub(crate) fn topological_sort<G: Adjacency>(graph: &G) -> Result<Vec<G::Id>, GraphError<G::Id>> {
+    let mut in_degree: HashMap<G::Id, usize> = graph.ids().map(|id| (id, graph.in_degree(id))).collect();
+    let mut ready: VecDeque<G::Id> = graph.ids().filter(|id| in_degree[id] == 0).collect();
+    let mut order = Vec::with_capacity(in_degree.len());
+    while let Some(id) = ready.pop_front() {
+        order.push(id);
+        graph.for_each_neighbor(id, |neighbor| {
+            let degree = in_degree.get_mut(&neighbor).expect("neighbors are always in the graph");
+            *degree -= 1;
+            if *degree == 0 {
+                ready.push_back(neighbor);
+            }
+        });
+    }
+    if order.len() == in_degree.len() {
+        Ok(order)
+    } else {
+        Err(GraphError::Cycle(find_cycle(graph).expect("Kahn's algorithm stalls only on a cycle")))
+    }
+}
+
+/// 深度优先搜索中遇到仍在当前路径上的节点，就找到了一个环
+pub(crate) fn find_cycle<G: Adjacency>(graph: &G) -> Option<Vec<G::Id>> {
+    #[derive(PartialEq)]
+    enum State {
+        OnPath,
+        Done,
+    }
+
+    let mut state: HashMap<G::Id, State> = HashMap::new();
+    for start in graph.ids() {
+        if state.contains_key(&start) {
+            continue;
+        }
+        // 用显式的栈代替递归：(节点, 它的后继, 下一个要访问的后继下标)
+        state.insert(start, State::OnPath);
+        let mut path = vec![(start, graph.neighbor_list(start), 0)];
+        while let Some((id, neighbors, next)) = path.last_mut() {
+            let Some(&child) = neighbors.get(*next) else {
+                state.insert(*id, State::Done);
+                path.pop();
+                continue;
+            };
+            *next += 1;
+            match state.get(&child) {
+                Some(State::OnPath) => {
+                    let begin = path.iter().position(|(id, _, _)| *id == child)?;
+                    return Some(path[begin..].iter().map(|(id, _, _)| *id).collect());
+                }
+                Some(State::Done) => {}
+                None => {
+                    state.insert(child, State::OnPath);
+                    path.push((child, graph.neighbor_list(child), 0));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// 边没有权重，广度优先搜索找到的就是边数最少的路径
+pub(crate) fn shortest_path<G: Adjacency>(graph: &G, from: G::Id, to: G::Id) -> Option<Vec<G::Id>> {
+    if !graph.contains(from) || !graph.contains(to) {
+        return None;
+    }
+    let mut previous: HashMap<G::Id, G::Id> = HashMap::new();
+    let mut queue = VecDeque::from([from]);
+    let mut visited = HashSet::from([from]);
+    while let Some(id) = queue.pop_front() {
+        if id == to {
+            let mut path = vec![to];
+            while let Some(&before) = previous.get(path.last()?) {
+                path.push(before);
+            }
+            path.reverse();
+            return Some(path);
+        }
+        graph.for_each_neighbor(id, |neighbor| {
+            if visited.insert(neighbor) {
+                previous.insert(neighbor, id);
+                queue.push_back(neighbor);
+            }
+        });
+    }
+    None
+}
@@ -0,0 +1,401 @@
+/*!
+ * 基于 arena 与代数句柄的有向图
+ *
+ * Graph 的每个节点都是一个 `Rc<RefCell<Node>>`：单独分配内存，每次访问都要检查借用，
+ * 每条边都要维护引用计数。ArenaGraph 把所有节点放在一个 Vec 里，边只保存下标：
+ *
+ * ```text
+ * ArenaGraph { slots: Vec<Slot<T>> }
+ * Slot<T>    { generation: u32, node: Option<Node<T>> }
+ * Node<T>    { value: T, neighbors: Vec<Handle>, parents: Vec<Handle> }
+ * Handle     { index: u32, generation: u32 }
+ * ```
+ *
+ * 删除节点后槽位会被复用。为了让旧的句柄不会误指向新节点，每个槽位记录一个代数（generation），
+ * 删除时加一；句柄中的代数与槽位不一致就说明它已经失效，检查只需要一次比较。
+ *
+ * 没有引用计数也就没有引用环，丢弃图时所有节点随 Vec 一起释放。
+ *
+ * ```rust
+ * use graph::ArenaGraph;
+ *
+ * let mut g = ArenaGraph::new();
+ * let a = g.add_node("a");
+ * let b = g.add_node("b");
+ * g.add_edge(a, b).unwrap();
+ * assert_eq!(g.bfs(a).collect::<Vec<_>>(), [a, b]);
+ *
+ * g.remove_node(b);
+ * let c = g.add_node("c");          // 复用了 b 的槽位
+ * assert_eq!(c.index(), b.index());
+ * assert_eq!(g.get(b), None);       // 旧句柄已经失效
+ * assert_eq!(g.get(c), Some(&"c"));
+ * ```
+ */
+
+use crate::algo::{self, Adjacency};
+use crate::error::GraphError;
+use std::collections::VecDeque;
+use std::fmt;
+
+/// 节点句柄：槽位下标加上代数
+///
+/// 节点被删除后句柄失效，即使槽位被新节点复用也不会误指向新节点
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Handle {
+    index: u32,
+    generation: u32,
+}
+
+impl Handle {
+    /// 槽位下标
+    pub fn index(self) -> usize {
+        self.index as usize
+    }
+
+    /// 槽位的代数，每删除一次节点加一
+    pub fn generation(self) -> u32 {
+        self.generation
+    }
+}
+
+impl fmt::Display for Handle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "n{}v{}", self.index, self.generation)
+    }
+}
+
+struct Node<T> {
+    value: T,
+    neighbors: Vec<Handle>,
+    parents: Vec<Handle>,
+}
+
+struct Slot<T> {
+    generation: u32,
+    node: Option<Node<T>>,
+}
+
+/// 泛型有向图，节点保存在连续的槽位中，API 与 [`Graph`](crate::Graph) 相同
+pub struct ArenaGraph<T> {
+    slots: Vec<Slot<T>>,
+    /// 空闲槽位的下标，添加节点时优先复用
+    free: Vec<u32>,
+    len: usize,
+    edge_count: usize,
+}
+
+impl<T> ArenaGraph<T> {
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// 预先为 `capacity` 个节点分配空间
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { slots: Vec::with_capacity(capacity), free: Vec::new(), len: 0, edge_count: 0 }
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.len
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// 所有节点的句柄，按槽位顺序（复用槽位时与添加顺序不同）
+    pub fn node_ids(&self) -> impl Iterator<Item = Handle> + '_ {
+        self.slots.iter().enumerate().filter(|(_, slot)| slot.node.is_some()).map(|(index, slot)| Handle {
+            index: index as u32,
+            generation: slot.generation,
+        })
+    }
+
+    /// 句柄仍然有效时返回节点：下标在范围内、代数一致、槽位被占用
+    fn node(&self, handle: Handle) -> Option<&Node<T>> {
+        let slot = self.slots.get(handle.index())?;
+        if slot.generation != handle.generation {
+            return None;
+        }
+        slot.node.as_ref()
+    }
+
+    fn node_mut(&mut self, handle: Handle) -> Option<&mut Node<T>> {
+        let slot = self.slots.get_mut(handle.index())?;
+        if slot.generation != handle.generation {
+            return None;
+        }
+        slot.node.as_mut()
+    }
+
+    pub fn contains_node(&self, handle: Handle) -> bool {
+        self.node(handle).is_some()
+    }
+
+    /// 添加节点，返回它的句柄
+    ///
+    /// 槽位数超过 u32::MAX 时 panic
+    pub fn add_node(&mut self, value: T) -> Handle {
+        let node = Some(Node { value, neighbors: Vec::new(), parents: Vec::new() });
+        self.len += 1;
+        if let Some(index) = self.free.pop() {
+            let slot = &mut self.slots[index as usize];
+            slot.node = node;
+            return Handle { index, generation: slot.generation };
+        }
+        let index = u32::try_from(self.slots.len()).expect("ArenaGraph supports at most u32::MAX slots");
+        self.slots.push(Slot { generation: 0, node });
+        Handle { index, generation: 0 }
+    }
+
+    /// 删除节点及其所有出边、入边，返回节点的值；句柄失效时返回 None
+    pub fn remove_node(&mut self, handle: Handle) -> Option<T> {
+        self.node(handle)?;
+        let slot = &mut self.slots[handle.index()];
+        let node = slot.node.take()?;
+        // 代数用尽的槽位不再复用，否则新句柄会与最早的旧句柄相同
+        if let Some(generation) = slot.generation.checked_add(1) {
+            slot.generation = generation;
+            self.free.push(handle.index);
+        }
+        self.len -= 1;
+
+        // 槽位的代数已经变了，自环指向的正是失效的句柄，下面的查找会自动跳过
+        self.edge_count -= node.neighbors.len();
+        for &child in &node.neighbors {
+            if let Some(child) = self.node_mut(child) {
+                child.parents.retain(|parent| *parent != handle);
+            }
+        }
+        for &parent in &node.parents {
+            if let Some(parent) = self.node_mut(parent) {
+                parent.neighbors.retain(|neighbor| *neighbor != handle);
+                self.edge_count -= 1;
+            }
+        }
+        Some(node.value)
+    }
+
+    /// 节点的值
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        self.node(handle).map(|node| &node.value)
+    }
+
+    /// 节点的值（可修改）
+    pub fn get_mut(&mut self, handle: Handle) -> Option<&mut T> {
+        self.node_mut(handle).map(|node| &mut node.value)
+    }
+
+    /// 添加一条从 `from` 到 `to` 的边；允许自环
+    pub fn add_edge(&mut self, from: Handle, to: Handle) -> Result<(), GraphError<Handle>> {
+        if !self.contains_node(to) {
+            return Err(GraphError::NodeNotFound(to));
+        }
+        let from_node = self.node_mut(from).ok_or(GraphError::NodeNotFound(from))?;
+        if from_node.neighbors.contains(&to) {
+            return Err(GraphError::EdgeExists { from, to });
+        }
+        from_node.neighbors.push(to);
+        self.node_mut(to).expect("checked above").parents.push(from);
+        self.edge_count += 1;
+        Ok(())
+    }
+
+    /// 删除从 `from` 到 `to` 的边，返回这条边是否存在
+    pub fn remove_edge(&mut self, from: Handle, to: Handle) -> bool {
+        if !self.contains_node(to) {
+            return false;
+        }
+        let Some(from_node) = self.node_mut(from) else {
+            return false;
+        };
+        let Some(index) = from_node.neighbors.iter().position(|neighbor| *neighbor == to) else {
+            return false;
+        };
+        from_node.neighbors.remove(index);
+        let to_node = self.node_mut(to).expect("checked above");
+        if let Some(index) = to_node.parents.iter().position(|parent| *parent == from) {
+            to_node.parents.remove(index);
+        }
+        self.edge_count -= 1;
+        true
+    }
+
+    pub fn contains_edge(&self, from: Handle, to: Handle) -> bool {
+        self.contains_node(to) && self.neighbors(from).contains(&to)
+    }
+
+    /// 后继节点，按添加边的顺序；句柄失效时为空
+    pub fn neighbors(&self, handle: Handle) -> &[Handle] {
+        self.node(handle).map_or(&[], |node| &node.neighbors)
+    }
+
+    /// 前驱节点；句柄失效时为空
+    pub fn parents(&self, handle: Handle) -> &[Handle] {
+        self.node(handle).map_or(&[], |node| &node.parents)
+    }
+
+    /// 从 `start` 开始的广度优先遍历；`start` 失效时为空
+    pub fn bfs(&self, start: Handle) -> Bfs<'_, T> {
+        let mut bfs = Bfs { graph: self, queue: VecDeque::new(), visited: vec![false; self.slots.len()] };
+        if self.contains_node(start) {
+            bfs.visited[start.index()] = true;
+            bfs.queue.push_back(start);
+        }
+        bfs
+    }
+
+    /// 从 `start` 开始的深度优先遍历（先序）；`start` 失效时为空
+    pub fn dfs(&self, start: Handle) -> Dfs<'_, T> {
+        let stack = if self.contains_node(start) { vec![start] } else { Vec::new() };
+        Dfs { graph: self, stack, visited: vec![false; self.slots.len()] }
+    }
+
+    /// 拓扑排序（Kahn 算法）：每条边的起点都排在终点之前
+    ///
+    /// 入度相同时按槽位顺序输出；有环时返回 [`GraphError::Cycle`]
+    pub fn topological_sort(&self) -> Result<Vec<Handle>, GraphError<Handle>> {
+        algo::topological_sort(self)
+    }
+
+    /// 找出一个环，首尾节点之间有边；无环时返回 None。自环是长度为 1 的环
+    pub fn find_cycle(&self) -> Option<Vec<Handle>> {
+        algo::find_cycle(self)
+    }
+
+    pub fn has_cycle(&self) -> bool {
+        self.find_cycle().is_some()
+    }
+
+    /// 从 `from` 到 `to` 经过边数最少的路径，包含首尾节点；不可达时返回 None
+    pub fn shortest_path(&self, from: Handle, to: Handle) -> Option<Vec<Handle>> {
+        algo::shortest_path(self, from, to)
+    }
+}
+
+impl<T> Adjacency for ArenaGraph<T> {
+    type Id = Handle;
+
+    fn ids(&self) -> impl Iterator<Item = Handle> + '_ {
+        self.node_ids()
+    }
+
+    fn contains(&self, handle: Handle) -> bool {
+        self.contains_node(handle)
+    }
+
+    fn for_each_neighbor(&self, handle: Handle, f: impl FnMut(Handle)) {
+        self.neighbors(handle).iter().copied().for_each(f);
+    }
+
+    fn in_degree(&self, handle: Handle) -> usize {
+        self.parents(handle).len()
+    }
+}
+
+impl<T> Default for ArenaGraph<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArenaGraph<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.node_ids().map(|handle| {
+                let node = self.node(handle).expect("node_ids yields live handles");
+                (handle, (&node.value, &node.neighbors))
+            }))
+            .finish()
+    }
+}
+
+/// 广度优先遍历，见 [`ArenaGraph::bfs`]
+///
+/// 已访问的节点按槽位下标记录在位图中，不需要哈希
+pub struct Bfs<'a, T> {
+    graph: &'a ArenaGraph<T>,
+    queue: VecDeque<Handle>,
+    visited: Vec<bool>,
+}
+
+impl<T> Iterator for Bfs<'_, T> {
+    type Item = Handle;
+
+    fn next(&mut self) -> Option<Handle> {
+        let handle = self.queue.pop_front()?;
+        for &neighbor in self.graph.neighbors(handle) {
+            if !std::mem::replace(&mut self.visited[neighbor.index()], true) {
+                self.queue.push_back(neighbor);
+            }
+        }
+        Some(handle)
+    }
+}
+
+/// 深度优先遍历（先序），见 [`ArenaGraph::dfs`]
+pub struct Dfs<'a, T> {
+    graph: &'a ArenaGraph<T>,
+    stack: Vec<Handle>,
+    visited: Vec<bool>,
+}
+
+impl<T> Iterator for Dfs<'_, T> {
+    type Item = Handle;
+
+    fn next(&mut self) -> Option<Handle> {
+        loop {
+            let handle = self.stack.pop()?;
+            if std::mem::replace(&mut self.visited[handle.index()], true) {
+                continue;
+            }
+            // 逆序入栈，先访问先添加的边，与递归写法的顺序一致
+            let unvisited = self.graph.neighbors(handle).iter().rev().filter(|n| !self.visited[n.index()]);
+            self.stack.extend(unvisited);
+            return Some(handle);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stale_handles_are_rejected_after_slot_reuse() {
+        let mut g = ArenaGraph::new();
+        let a = g.add_node(1);
+        let b = g.add_node(2);
+        g.add_edge(a, b).unwrap();
+        g.add_edge(b, b).unwrap();
+
+        assert_eq!(g.remove_node(b), Some(2));
+        assert_eq!(g.edge_count(), 0);
+        let c = g.add_node(3);
+        assert_eq!((c.index(), c.generation()), (b.index(), b.generation() + 1));
+
+        assert!(!g.contains_node(b) && g.get(b).is_none());
+        assert_eq!(g.add_edge(a, b), Err(GraphError::NodeNotFound(b)));
+        assert!(!g.remove_edge(a, b));
+        assert_eq!(g.remove_node(b), None);
+        assert_eq!(g.neighbors(a), []);
+        assert_eq!(g.get(c), Some(&3));
+    }
+
+    #[test]
+    fn exhausted_slots_are_retired() {
+        let mut g = ArenaGraph::new();
+        let a = g.add_node('a');
+        g.slots[a.index()].generation = u32::MAX;
+        let a = Handle { index: a.index, generation: u32::MAX };
+
+        assert_eq!(g.remove_node(a), Some('a'));
+        let b = g.add_node('b');
+        assert_ne!(b.index(), a.index());
+        assert!(g.free.is_empty());
+    }
+}
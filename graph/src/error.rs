@@ -0,0 +1,42 @@
+/*!
+ * 图操作的错误
+ *
+ * 两种实现共用同一个错误类型，只是节点的标识不同：
+ * Graph 使用 NodeId，ArenaGraph 使用带代数的 Handle。
+ */
+
+use crate::rc::NodeId;
+use std::error::Error;
+use std::fmt;
+
+/// 图操作的错误，`Id` 是节点的标识
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GraphError<Id = NodeId> {
+    /// 节点不存在（从未添加，或者已经删除）
+    NodeNotFound(Id),
+    /// 这条边已经存在；图中两个节点之间同一方向最多一条边
+    EdgeExists { from: Id, to: Id },
+    /// 图中有环，无法拓扑排序；附带其中一个环，首尾节点之间有边
+    Cycle(Vec<Id>),
+}
+
+impl<Id: fmt::Display> fmt::Display for GraphError<Id> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphError::NodeNotFound(id) => write!(f, "node {} not found", id),
+            GraphError::EdgeExists { from, to } => write!(f, "edge {} -> {} already exists", from, to),
+            GraphError::Cycle(cycle) => {
+                f.write_str("graph contains a cycle: ")?;
+                for id in cycle {
+                    write!(f, "{} -> ", id)?;
+                }
+                match cycle.first() {
+                    Some(first) => write!(f, "{}", first),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+impl<Id: fmt::Debug + fmt::Display> Error for GraphError<Id> {}
@@ -0,0 +1,31 @@
+/*!
+ * 泛型有向图
+ *
+ * wrappers.rs 的第 8 部分用 `Rc<RefCell<Node>>` 和 `Weak` 手工搭了一个三节点的链表。
+ * 这个 crate 把同样的写法整理成可复用的 `Graph<T>`，并提供 API 相同的 arena 实现 `ArenaGraph<T>`：
+ *
+ * - 添加 / 删除节点和边；
+ * - 广度优先、深度优先遍历（迭代器）；
+ * - 拓扑排序与环检测；
+ * - 最短路径（按边数）。
+ *
+ * 模块划分：
+ * - rc：基于 Rc / RefCell / Weak 的实现，出边是强引用，入边是弱引用
+ * - arena：节点放在连续的槽位中，用带代数的句柄访问，没有引用计数和运行时借用检查
+ * - error：两种实现共用的 GraphError
+ *
+ * 两者的取舍见 README 与 `cargo bench` 的对比结果。
+ *
+ * 相关文档：
+ * - https://doc.rust-lang.org/book/ch15-06-reference-cycles.html
+ * - https://doc.rust-lang.org/std/rc/struct.Weak.html
+ */
+
+mod algo;
+pub mod arena;
+pub mod error;
+pub mod rc;
+
+pub use arena::{ArenaGraph, Handle};
+pub use error::GraphError;
+pub use rc::{Bfs, Dfs, Graph, NodeId};
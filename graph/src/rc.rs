@@ -0,0 +1,400 @@
+/*!
+ * 基于 Rc / RefCell / Weak 的有向图
+ *
+ * 节点的写法与 wrappers.rs 第 8 部分相同：
+ *
+ * ```text
+ * Node { value, neighbors: Vec<Rc<RefCell<Node>>>, parents: Vec<Weak<RefCell<Node>>> }
+ * ```
+ *
+ * - 出边 `neighbors` 是强引用，沿着它可以直接走到后继节点；
+ * - 入边 `parents` 是弱引用，不增加引用计数，因此父子之间不会形成强引用环；
+ * - Graph 本身持有每个节点的一个强引用，NodeId 只是查找用的编号，不持有节点。
+ *
+ * 出边之间仍然可能形成环（a → b → a），单靠引用计数永远释放不了。
+ * Graph 在 Drop 时清空所有节点的边，把这些环拆开，所以丢弃图不会泄漏内存。
+ *
+ * ```rust
+ * use graph::Graph;
+ *
+ * let mut g = Graph::new();
+ * let a = g.add_node("a");
+ * let b = g.add_node("b");
+ * let c = g.add_node("c");
+ * g.add_edge(a, b).unwrap();
+ * g.add_edge(b, c).unwrap();
+ * g.add_edge(a, c).unwrap();
+ *
+ * assert_eq!(g.bfs(a).collect::<Vec<_>>(), [a, b, c]);
+ * assert_eq!(g.topological_sort().unwrap(), [a, b, c]);
+ * assert_eq!(g.shortest_path(a, c), Some(vec![a, c]));
+ *
+ * g.add_edge(c, a).unwrap();
+ * assert!(g.has_cycle());
+ * ```
+ */
+
+use crate::algo::{self, Adjacency};
+use crate::error::GraphError;
+use std::cell::{Ref, RefCell, RefMut};
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::fmt;
+use std::marker::PhantomData;
+use std::rc::{Rc, Weak};
+
+/// 节点编号
+///
+/// 编号按添加顺序递增，删除节点后不会复用，因此旧的编号不会误指向新节点
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(usize);
+
+impl NodeId {
+    /// 编号的数值
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+impl fmt::Display for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "n{}", self.0)
+    }
+}
+
+type Link<T> = Rc<RefCell<Node<T>>>;
+
+struct Node<T> {
+    id: NodeId,
+    value: T,
+    /// 出边：强引用
+    neighbors: Vec<Link<T>>,
+    /// 入边：弱引用，避免父子之间的强引用环
+    parents: Vec<Weak<RefCell<Node<T>>>>,
+}
+
+impl<T> Node<T> {
+    fn neighbor_ids(&self) -> Vec<NodeId> {
+        self.neighbors.iter().map(|neighbor| neighbor.borrow().id).collect()
+    }
+}
+
+/// 泛型有向图，节点保存 `T` 类型的值
+pub struct Graph<T> {
+    /// 按编号排序，遍历所有节点的顺序就是添加顺序
+    nodes: BTreeMap<NodeId, Link<T>>,
+    next_id: usize,
+    edge_count: usize,
+}
+
+impl<T> Graph<T> {
+    pub fn new() -> Self {
+        Self { nodes: BTreeMap::new(), next_id: 0, edge_count: 0 }
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// 所有节点的编号，按添加顺序
+    pub fn node_ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.nodes.keys().copied()
+    }
+
+    pub fn contains_node(&self, id: NodeId) -> bool {
+        self.nodes.contains_key(&id)
+    }
+
+    fn link(&self, id: NodeId) -> Result<&Link<T>, GraphError> {
+        self.nodes.get(&id).ok_or(GraphError::NodeNotFound(id))
+    }
+
+    /// 添加节点，返回它的编号
+    pub fn add_node(&mut self, value: T) -> NodeId {
+        let id = NodeId(self.next_id);
+        self.next_id += 1;
+        let node = Node { id, value, neighbors: Vec::new(), parents: Vec::new() };
+        self.nodes.insert(id, Rc::new(RefCell::new(node)));
+        id
+    }
+
+    /// 删除节点及其所有出边、入边，返回节点的值；节点不存在时返回 None
+    pub fn remove_node(&mut self, id: NodeId) -> Option<T> {
+        let link = self.nodes.remove(&id)?;
+        let (neighbors, parents) = {
+            let mut node = link.borrow_mut();
+            (std::mem::take(&mut node.neighbors), std::mem::take(&mut node.parents))
+        };
+
+        // 出边：从后继节点的 parents 中去掉自己（自环的那一份已经随 take 一起清掉了）
+        self.edge_count -= neighbors.len();
+        for child in neighbors.iter().filter(|child| !Rc::ptr_eq(child, &link)) {
+            child.borrow_mut().parents.retain(|parent| parent.as_ptr() != Rc::as_ptr(&link));
+        }
+        // 入边：从前驱节点的 neighbors 中去掉自己
+        for parent in parents.iter().filter_map(Weak::upgrade).filter(|parent| !Rc::ptr_eq(parent, &link)) {
+            let mut parent = parent.borrow_mut();
+            parent.neighbors.retain(|neighbor| !Rc::ptr_eq(neighbor, &link));
+            self.edge_count -= 1;
+        }
+        drop(neighbors);
+
+        // 其他节点已经不再引用它，这里是最后一个强引用
+        Rc::into_inner(link).map(|node| node.into_inner().value)
+    }
+
+    /// 节点的值
+    pub fn get(&self, id: NodeId) -> Option<Ref<'_, T>> {
+        self.nodes.get(&id).map(|link| Ref::map(link.borrow(), |node| &node.value))
+    }
+
+    /// 节点的值（可修改）
+    pub fn get_mut(&mut self, id: NodeId) -> Option<RefMut<'_, T>> {
+        self.nodes.get(&id).map(|link| RefMut::map(link.borrow_mut(), |node| &mut node.value))
+    }
+
+    /// 添加一条从 `from` 到 `to` 的边；允许自环
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId) -> Result<(), GraphError> {
+        let from_link = Rc::clone(self.link(from)?);
+        let to_link = Rc::clone(self.link(to)?);
+        if from_link.borrow().neighbors.iter().any(|neighbor| Rc::ptr_eq(neighbor, &to_link)) {
+            return Err(GraphError::EdgeExists { from, to });
+        }
+        // 自环时 from 与 to 是同一个 RefCell，两次可变借用必须分开
+        from_link.borrow_mut().neighbors.push(Rc::clone(&to_link));
+        to_link.borrow_mut().parents.push(Rc::downgrade(&from_link));
+        self.edge_count += 1;
+        Ok(())
+    }
+
+    /// 删除从 `from` 到 `to` 的边，返回这条边是否存在
+    pub fn remove_edge(&mut self, from: NodeId, to: NodeId) -> bool {
+        let (Ok(from_link), Ok(to_link)) = (self.link(from), self.link(to)) else {
+            return false;
+        };
+        let removed = {
+            let mut node = from_link.borrow_mut();
+            match node.neighbors.iter().position(|neighbor| Rc::ptr_eq(neighbor, to_link)) {
+                Some(index) => {
+                    node.neighbors.remove(index);
+                    true
+                }
+                None => false,
+            }
+        };
+        if removed {
+            to_link.borrow_mut().parents.retain(|parent| parent.as_ptr() != Rc::as_ptr(from_link));
+            self.edge_count -= 1;
+        }
+        removed
+    }
+
+    pub fn contains_edge(&self, from: NodeId, to: NodeId) -> bool {
+        match (self.link(from), self.link(to)) {
+            (Ok(from), Ok(to)) => from.borrow().neighbors.iter().any(|neighbor| Rc::ptr_eq(neighbor, to)),
+            _ => false,
+        }
+    }
+
+    /// 后继节点（出边指向的节点），按添加边的顺序；节点不存在时为空
+    pub fn neighbors(&self, id: NodeId) -> Vec<NodeId> {
+        self.nodes.get(&id).map(|link| link.borrow().neighbor_ids()).unwrap_or_default()
+    }
+
+    /// 前驱节点（入边来自的节点），通过弱引用找到
+    pub fn parents(&self, id: NodeId) -> Vec<NodeId> {
+        self.nodes
+            .get(&id)
+            .map(|link| {
+                link.borrow().parents.iter().filter_map(Weak::upgrade).map(|parent| parent.borrow().id).collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// 从 `start` 开始的广度优先遍历；`start` 不存在时为空
+    pub fn bfs(&self, start: NodeId) -> Bfs<'_, T> {
+        let mut bfs = Bfs { queue: VecDeque::new(), visited: HashSet::new(), graph: PhantomData };
+        if let Some(link) = self.nodes.get(&start) {
+            bfs.visited.insert(start);
+            bfs.queue.push_back(Rc::clone(link));
+        }
+        bfs
+    }
+
+    /// 从 `start` 开始的深度优先遍历（先序）；`start` 不存在时为空
+    pub fn dfs(&self, start: NodeId) -> Dfs<'_, T> {
+        Dfs { stack: self.nodes.get(&start).into_iter().cloned().collect(), visited: HashSet::new(), graph: PhantomData }
+    }
+
+    /// 拓扑排序（Kahn 算法）：每条边的起点都排在终点之前
+    ///
+    /// 入度相同时按添加顺序输出，结果是确定的；有环时返回 [`GraphError::Cycle`]
+    pub fn topological_sort(&self) -> Result<Vec<NodeId>, GraphError> {
+        algo::topological_sort(self)
+    }
+
+    /// 找出一个环，首尾节点之间有边；无环时返回 None。自环是长度为 1 的环
+    pub fn find_cycle(&self) -> Option<Vec<NodeId>> {
+        algo::find_cycle(self)
+    }
+
+    pub fn has_cycle(&self) -> bool {
+        self.find_cycle().is_some()
+    }
+
+    /// 从 `from` 到 `to` 经过边数最少的路径，包含首尾节点；不可达时返回 None
+    ///
+    /// 边没有权重，因此用广度优先搜索即可
+    pub fn shortest_path(&self, from: NodeId, to: NodeId) -> Option<Vec<NodeId>> {
+        algo::shortest_path(self, from, to)
+    }
+}
+
+impl<T> Adjacency for Graph<T> {
+    type Id = NodeId;
+
+    fn ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.nodes.keys().copied()
+    }
+
+    fn contains(&self, id: NodeId) -> bool {
+        self.contains_node(id)
+    }
+
+    fn for_each_neighbor(&self, id: NodeId, mut f: impl FnMut(NodeId)) {
+        if let Some(link) = self.nodes.get(&id) {
+            for neighbor in &link.borrow().neighbors {
+                f(neighbor.borrow().id);
+            }
+        }
+    }
+
+    fn in_degree(&self, id: NodeId) -> usize {
+        self.nodes.get(&id).map_or(0, |link| link.borrow().parents.len())
+    }
+}
+
+impl<T> Default for Graph<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 出边之间的环靠引用计数无法释放，这里清空所有的边把环拆开
+impl<T> Drop for Graph<T> {
+    fn drop(&mut self) {
+        for link in self.nodes.values() {
+            let mut node = link.borrow_mut();
+            node.neighbors.clear();
+            node.parents.clear();
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Graph<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.nodes.iter().map(|(id, link)| {
+                let node = link.borrow();
+                (*id, (format!("{:?}", node.value), node.neighbor_ids()))
+            }))
+            .finish()
+    }
+}
+
+/// 广度优先遍历，见 [`Graph::bfs`]
+///
+/// 队列中保存节点的强引用，沿着 neighbors 前进；借用图的期间图不能被修改
+pub struct Bfs<'a, T> {
+    queue: VecDeque<Link<T>>,
+    visited: HashSet<NodeId>,
+    graph: PhantomData<&'a Graph<T>>,
+}
+
+impl<T> Iterator for Bfs<'_, T> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<NodeId> {
+        let link = self.queue.pop_front()?;
+        let node = link.borrow();
+        for neighbor in &node.neighbors {
+            if self.visited.insert(neighbor.borrow().id) {
+                self.queue.push_back(Rc::clone(neighbor));
+            }
+        }
+        Some(node.id)
+    }
+}
+
+/// 深度优先遍历（先序），见 [`Graph::dfs`]
+pub struct Dfs<'a, T> {
+    stack: Vec<Link<T>>,
+    visited: HashSet<NodeId>,
+    graph: PhantomData<&'a Graph<T>>,
+}
+
+impl<T> Iterator for Dfs<'_, T> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<NodeId> {
+        loop {
+            let link = self.stack.pop()?;
+            let node = link.borrow();
+            if !self.visited.insert(node.id) {
+                continue;
+            }
+            // 逆序入栈，先访问先添加的边，与递归写法的顺序一致
+            for neighbor in node.neighbors.iter().rev() {
+                if !self.visited.contains(&neighbor.borrow().id) {
+                    self.stack.push(Rc::clone(neighbor));
+                }
+            }
+            return Some(node.id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edges_hold_strong_forward_and_weak_backward_references() {
+        let mut g = Graph::new();
+        let a = g.add_node('a');
+        let b = g.add_node('b');
+        g.add_edge(a, b).unwrap();
+
+        // b：图本身 + a 的出边；a：只有图本身，b 对它是弱引用
+        assert_eq!(Rc::strong_count(&g.nodes[&b]), 2);
+        assert_eq!(Rc::strong_count(&g.nodes[&a]), 1);
+        assert_eq!(Rc::weak_count(&g.nodes[&a]), 1);
+
+        assert!(g.remove_edge(a, b));
+        assert_eq!(Rc::strong_count(&g.nodes[&b]), 1);
+        assert_eq!(Rc::weak_count(&g.nodes[&a]), 0);
+    }
+
+    #[test]
+    fn removing_a_node_in_a_cycle_releases_every_reference() {
+        let mut g = Graph::new();
+        let a = g.add_node(String::from("a"));
+        let b = g.add_node(String::from("b"));
+        g.add_edge(a, b).unwrap();
+        g.add_edge(b, a).unwrap();
+        g.add_edge(b, b).unwrap();
+
+        assert_eq!(g.remove_node(b), Some(String::from("b")));
+        assert_eq!((g.node_count(), g.edge_count()), (1, 0));
+        assert_eq!(Rc::strong_count(&g.nodes[&a]), 1);
+        assert_eq!(Rc::weak_count(&g.nodes[&a]), 0);
+        assert!(g.neighbors(a).is_empty() && g.parents(a).is_empty());
+    }
+}
@@ -0,0 +1,48 @@
+// ArenaGraph 的公开 API：与 tests/graph.rs 相同的场景，外加失效句柄的检测
+
+#[macro_use]
+mod common;
+
+use graph::{ArenaGraph, GraphError};
+
+#[test]
+fn same_results_as_the_rc_graph() {
+    let (mut g, [a, b, c, d, e]) = diamond!(ArenaGraph);
+    assert_eq!(g.add_edge(a, b), Err(GraphError::EdgeExists { from: a, to: b }));
+    assert_eq!(g.neighbors(a), [b, c]);
+    assert_eq!(g.parents(d), [b, c]);
+
+    assert_eq!(g.bfs(a).collect::<Vec<_>>(), [a, b, c, d]);
+    assert_eq!(g.dfs(a).collect::<Vec<_>>(), [a, b, d, c]);
+    assert_eq!(g.topological_sort(), Ok(vec![a, e, b, c, d]));
+    assert_eq!(g.shortest_path(a, d), Some(vec![a, b, d]));
+    assert_eq!(g.shortest_path(d, a), None);
+
+    g.add_edge(d, a).unwrap();
+    let cycle = g.find_cycle().unwrap();
+    assert!(g.contains_edge(*cycle.last().unwrap(), cycle[0]));
+    assert!(matches!(g.topological_sort(), Err(GraphError::Cycle(_))));
+}
+
+#[test]
+fn removed_nodes_leave_stale_handles() {
+    let (mut g, [a, b, c, d, e]) = diamond!(ArenaGraph);
+    *g.get_mut(a).unwrap() = "A";
+    assert_eq!(g.remove_node(b), Some("b"));
+    assert_eq!((g.node_count(), g.edge_count()), (4, 2));
+    assert_eq!(g.parents(d), [c]);
+
+    // 新节点复用 b 的槽位，但 b 的句柄仍然失效
+    let f = g.add_node("f");
+    assert_eq!(f.index(), b.index());
+    assert_ne!(f, b);
+    assert_eq!(g.get(b), None);
+    assert_eq!(g.add_edge(b, d), Err(GraphError::NodeNotFound(b)));
+    assert_eq!(g.bfs(b).count(), 0);
+    assert_eq!(g.shortest_path(a, b), None);
+
+    // 排序只会看到有效的节点；入度相同时按槽位顺序，f 排在 e 之前
+    g.add_edge(f, a).unwrap();
+    assert_eq!(g.topological_sort(), Ok(vec![f, e, a, c, d]));
+    assert_eq!(*g.get(a).unwrap(), "A");
+}
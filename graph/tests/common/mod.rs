@@ -0,0 +1,17 @@
+// tests/graph.rs 与 tests/arena.rs 共用的测试数据
+
+/// 菱形依赖：a → b → d，a → c → d，再加一个孤立的 e
+///
+/// 展开为 `(图, [a, b, c, d, e])`。Graph 和 ArenaGraph 的方法同名但没有共同的 trait，
+/// 所以用宏而不是泛型函数
+macro_rules! diamond {
+    ($graph:ident) => {{
+        let mut g = $graph::new();
+        let ids = ["a", "b", "c", "d", "e"].map(|name| g.add_node(name));
+        let [a, b, c, d, _] = ids;
+        for (from, to) in [(a, b), (a, c), (b, d), (c, d)] {
+            g.add_edge(from, to).unwrap();
+        }
+        (g, ids)
+    }};
+}
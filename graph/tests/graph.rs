@@ -0,0 +1,104 @@
+// Graph 的公开 API：增删、遍历、拓扑排序、环检测、最短路径以及释放
+
+#[macro_use]
+mod common;
+
+use graph::{Graph, GraphError};
+use std::cell::Cell;
+use std::rc::Rc;
+
+#[test]
+fn nodes_and_edges_can_be_added_and_removed() {
+    let (mut g, [a, b, c, d, e]) = diamond!(Graph);
+    assert_eq!((g.node_count(), g.edge_count()), (5, 4));
+    assert_eq!(g.add_edge(a, b), Err(GraphError::EdgeExists { from: a, to: b }));
+    assert_eq!(g.neighbors(a), [b, c]);
+    assert_eq!(g.parents(d), [b, c]);
+
+    *g.get_mut(e).unwrap() = "E";
+    assert_eq!(*g.get(e).unwrap(), "E");
+
+    assert_eq!(g.remove_node(b), Some("b"));
+    assert_eq!((g.node_count(), g.edge_count()), (4, 2));
+    assert_eq!(g.parents(d), [c]);
+    assert!(g.get(b).is_none());
+    assert_eq!(g.add_edge(b, d), Err(GraphError::NodeNotFound(b)));
+
+    // 删除后的编号不会被复用
+    let f = g.add_node("f");
+    assert_ne!(f, b);
+
+    assert!(g.remove_edge(c, d));
+    assert!(!g.remove_edge(c, d));
+    assert!(!g.contains_edge(c, d));
+    assert_eq!(g.edge_count(), 1);
+}
+
+#[test]
+fn traversals_follow_edge_order() {
+    let (g, [a, b, c, d, e]) = diamond!(Graph);
+    assert_eq!(g.bfs(a).collect::<Vec<_>>(), [a, b, c, d]);
+    assert_eq!(g.dfs(a).collect::<Vec<_>>(), [a, b, d, c]);
+    assert_eq!(g.bfs(e).collect::<Vec<_>>(), [e]);
+    assert_eq!(g.dfs(d).count(), 1);
+}
+
+#[test]
+fn topological_sort_and_cycles() {
+    let (mut g, [a, b, c, d, e]) = diamond!(Graph);
+    assert_eq!(g.topological_sort(), Ok(vec![a, e, b, c, d]));
+    assert!(!g.has_cycle());
+
+    g.add_edge(d, a).unwrap();
+    let cycle = g.find_cycle().unwrap();
+    for pair in cycle.windows(2) {
+        assert!(g.contains_edge(pair[0], pair[1]));
+    }
+    assert!(g.contains_edge(*cycle.last().unwrap(), cycle[0]));
+    assert!(matches!(g.topological_sort(), Err(GraphError::Cycle(_))));
+
+    let mut single = Graph::new();
+    let x = single.add_node(());
+    single.add_edge(x, x).unwrap();
+    assert_eq!(single.find_cycle(), Some(vec![x]));
+}
+
+#[test]
+fn shortest_path_counts_edges() {
+    let (mut g, [a, b, _, d, e]) = diamond!(Graph);
+    assert_eq!(g.shortest_path(a, d), Some(vec![a, b, d]));
+    assert_eq!(g.shortest_path(a, a), Some(vec![a]));
+    assert_eq!(g.shortest_path(a, e), None);
+    assert_eq!(g.shortest_path(d, a), None);
+
+    g.add_edge(a, d).unwrap();
+    assert_eq!(g.shortest_path(a, d), Some(vec![a, d]));
+}
+
+/// 被释放时计数的值
+struct Tracked(Rc<Cell<usize>>);
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+#[test]
+fn dropping_a_cyclic_graph_frees_every_node() {
+    let dropped = Rc::new(Cell::new(0));
+    let mut g = Graph::new();
+    let ids: Vec<_> = (0..4).map(|_| g.add_node(Tracked(Rc::clone(&dropped)))).collect();
+    for (i, &from) in ids.iter().enumerate() {
+        // 环 0 → 1 → 2 → 3 → 0，外加自环和反向边
+        g.add_edge(from, ids[(i + 1) % ids.len()]).unwrap();
+        g.add_edge(from, from).unwrap();
+        g.add_edge(ids[(i + 1) % ids.len()], from).ok();
+    }
+    assert!(g.has_cycle());
+
+    drop(g.remove_node(ids[0]));
+    assert_eq!(dropped.get(), 1);
+    drop(g);
+    assert_eq!(dropped.get(), 4);
+}
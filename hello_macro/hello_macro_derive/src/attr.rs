@@ -0,0 +1,202 @@
+// `#[hello(...)]` 辅助属性解析
+//
+// 辅助属性（helper attribute）需要在 #[proc_macro_derive] 中声明，
+// 编译器才会允许它出现在派生类型上，并把它原样放进 DeriveInput::attrs。
+//
+// 支持的写法：
+//
+//     #[derive(HelloMacro)]
+//     #[hello(greeting = "喵～ 我是 {name}！", name = "小猫")]
+//     struct Cat;
+//
+//     #[derive(HelloMacro)]
+//     #[hello(bound = "T: Clone")]
+//     struct Wrapper<T>(T);
+//
+//     #[derive(HelloMacro)]
+//     #[hello(zh(greeting = "汪！我是{name}", name = "旺财"), en(name = "Wangcai"))]  // 按语言定制
+//     struct Dog;
+//
+//     #[derive(HelloMacro)]
+//     #[hello(crate = "my_wrapper::hello")]  // 通过其他 crate 重新导出 hello_macro 时使用
+//     struct Fox;
+//
+//     #[derive(HelloMacro)]
+//     enum Shape {
+//         #[hello(greeting = "{variant} 的半径是 {radius}")]
+//         Circle { radius: f64 },
+//     }
+
+use quote::ToTokens;
+use syn::meta::ParseNestedMeta;
+use syn::punctuated::Punctuated;
+use std::collections::BTreeMap;
+
+use syn::{token, Attribute, LitStr, Path, Token, WherePredicate};
+
+use crate::locale::{self, LocaleDef};
+use crate::template::Template;
+
+/// `#[hello(...)]` 出现的位置，决定哪些键是合法的
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttrTarget {
+    /// 派生类型本身（结构体或枚举）
+    Type,
+    /// 枚举的某个变体
+    Variant,
+}
+
+/// 从 `#[hello(...)]` 中收集到的配置
+#[derive(Default)]
+pub struct HelloAttrs {
+    /// 自定义问候语模板，未指定时使用默认模板
+    pub greeting: Option<Template>,
+    /// 覆盖 `{name}` 占位符的显示名称
+    pub name: Option<LitStr>,
+    /// 额外添加到生成的 impl 上的 where 约束
+    pub bounds: Vec<WherePredicate>,
+    /// hello_macro crate 的路径，默认为 `::hello_macro`
+    pub krate: Option<Path>,
+    /// 按语言定制的问候语和名称，键为语言代码（如 `"zh"`）
+    pub localized: BTreeMap<&'static str, Localized>,
+}
+
+/// `#[hello(zh(greeting = "...", name = "..."))]` 中某种语言的设置
+#[derive(Default)]
+pub struct Localized {
+    pub greeting: Option<Template>,
+    pub name: Option<LitStr>,
+}
+
+impl HelloAttrs {
+    /// 解析类型上的所有 `#[hello(...)]` 属性，其他属性会被忽略
+    ///
+    /// 所有错误都带有指向出错位置的 span，例如未知的键、重复的键、
+    /// 缺少 `= "..."`、值不是字符串字面量，或者在变体上使用了 `bound` / `crate`
+    pub fn from_attrs(attrs: &[Attribute], target: AttrTarget) -> syn::Result<Self> {
+        let mut out = HelloAttrs::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("hello")) {
+            attr.parse_nested_meta(|meta| {
+                let key = meta.path.to_token_stream().to_string().replace(' ', "");
+                match key.as_str() {
+                    "greeting" => {
+                        if out.greeting.is_some() {
+                            return Err(meta.error("duplicate `greeting` in #[hello(...)]"));
+                        }
+                        out.greeting = Some(Template::parse(&string_value(&meta, "greeting")?)?);
+                    }
+                    "name" => {
+                        if out.name.is_some() {
+                            return Err(meta.error("duplicate `name` in #[hello(...)]"));
+                        }
+                        out.name = Some(string_value(&meta, "name")?);
+                    }
+                    "bound" => {
+                        if target == AttrTarget::Variant {
+                            return Err(meta.error(
+                                "`bound` applies to the whole impl and cannot be used on an enum variant",
+                            ));
+                        }
+                        let lit = string_value(&meta, "bound")?;
+                        let bounds = lit.parse_with(
+                            Punctuated::<WherePredicate, Token![,]>::parse_terminated,
+                        )?;
+                        out.bounds.extend(bounds);
+                    }
+                    "crate" => {
+                        if target == AttrTarget::Variant {
+                            return Err(meta.error(
+                                "`crate` applies to the whole impl and cannot be used on an enum variant",
+                            ));
+                        }
+                        if out.krate.is_some() {
+                            return Err(meta.error("duplicate `crate` in #[hello(...)]"));
+                        }
+                        out.krate = Some(string_value(&meta, "crate")?.parse()?);
+                    }
+                    _ => match locale::find(&key) {
+                        Some(locale) => {
+                            let localized = out.localized.entry(locale.key).or_default();
+                            parse_localized(&meta, locale, localized)?;
+                        }
+                        None => {
+                            return Err(meta.error(format!(
+                                "unknown hello attribute `{}`; expected one of `greeting`, `name`, `bound`, `crate` or a locale ({})",
+                                key,
+                                locale::keys()
+                            )))
+                        }
+                    },
+                }
+                Ok(())
+            })?;
+        }
+        Ok(out)
+    }
+}
+
+impl HelloAttrs {
+    /// 某种语言下使用的问候语：该语言专属的优先，其次是不分语言的 `greeting`
+    pub fn greeting_for(&self, locale: &LocaleDef) -> Option<&Template> {
+        self.localized
+            .get(locale.key)
+            .and_then(|l| l.greeting.as_ref())
+            .or(self.greeting.as_ref())
+    }
+
+    /// 某种语言下 `{name}` 的显示名称：该语言专属的优先，其次是不分语言的 `name`
+    pub fn name_for(&self, locale: &LocaleDef) -> Option<&LitStr> {
+        self.localized
+            .get(locale.key)
+            .and_then(|l| l.name.as_ref())
+            .or(self.name.as_ref())
+    }
+
+    /// 生成代码中引用 hello_macro crate 时使用的路径
+    ///
+    /// 默认为 `::hello_macro`；下游 crate 重新导出 hello_macro 时，
+    /// 可以用 `#[hello(crate = "...")]` 指向重新导出的位置
+    pub fn crate_path(&self) -> Path {
+        self.krate.clone().unwrap_or_else(|| syn::parse_quote!(::hello_macro))
+    }
+}
+
+/// 解析 `zh(greeting = "...", name = "...")`，同一种语言可以分多次书写，但每个键只能出现一次
+fn parse_localized(meta: &ParseNestedMeta, locale: &LocaleDef, out: &mut Localized) -> syn::Result<()> {
+    if !meta.input.peek(token::Paren) {
+        return Err(meta.error(format!(
+            "expected `{}(greeting = \"...\", name = \"...\")`",
+            locale.key
+        )));
+    }
+    meta.parse_nested_meta(|inner| {
+        if inner.path.is_ident("greeting") {
+            if out.greeting.is_some() {
+                return Err(inner.error(format!("duplicate `greeting` in #[hello({}(...))]", locale.key)));
+            }
+            out.greeting = Some(Template::parse(&string_value(&inner, "greeting")?)?);
+        } else if inner.path.is_ident("name") {
+            if out.name.is_some() {
+                return Err(inner.error(format!("duplicate `name` in #[hello({}(...))]", locale.key)));
+            }
+            out.name = Some(string_value(&inner, "name")?);
+        } else {
+            let key = inner.path.to_token_stream().to_string().replace(' ', "");
+            return Err(inner.error(format!(
+                "unknown key `{}` in #[hello({}(...))]; expected `greeting` or `name`",
+                key, locale.key
+            )));
+        }
+        Ok(())
+    })
+}
+
+/// 读取 `key = "..."` 中的字符串字面量
+fn string_value(meta: &ParseNestedMeta, key: &str) -> syn::Result<LitStr> {
+    if !meta.input.peek(Token![=]) {
+        return Err(meta.error(format!("expected `{} = \"...\"`", key)));
+    }
+    meta.value()?.parse::<LitStr>().map_err(|err| {
+        syn::Error::new(err.span(), format!("expected a string literal for `{}`", key))
+    })
+}
@@ -0,0 +1,54 @@
+// hello_expand：查看 #[derive(HelloMacro)] 实际展开成的代码
+//
+// 用法：
+//
+//     cargo run --features inspect --bin hello_expand -- path/to/file.rs
+//     cat snippet.rs | cargo run --features inspect --bin hello_expand
+//
+// 过程宏 crate 只能导出宏，不能被普通 crate 作为库链接，
+// 所以这里通过 #[path] 直接引入派生宏的代码生成模块，保证看到的就是真实的展开结果。
+
+#[path = "../attr.rs"]
+mod attr;
+#[path = "../expand.rs"]
+mod expand;
+#[path = "../hello.rs"]
+mod hello;
+#[path = "../locale.rs"]
+mod locale;
+#[path = "../template.rs"]
+mod template;
+
+use std::io::Read;
+use std::process::ExitCode;
+use std::{env, fs, io};
+
+fn main() -> ExitCode {
+    // 不带参数或参数为 `-` 时从标准输入读取
+    let (label, source) = match env::args().nth(1).filter(|arg| arg != "-") {
+        Some(path) => (path.clone(), fs::read_to_string(&path)),
+        None => {
+            let mut source = String::new();
+            let result = io::stdin().read_to_string(&mut source).map(|_| source);
+            ("<stdin>".to_string(), result)
+        }
+    };
+    let source = match source {
+        Ok(source) => source,
+        Err(err) => {
+            eprintln!("error: cannot read {}: {}", label, err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match expand::expand_source(&source) {
+        Ok(code) => {
+            print!("{}", code);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: cannot parse {}: {}", label, err);
+            ExitCode::FAILURE
+        }
+    }
+}
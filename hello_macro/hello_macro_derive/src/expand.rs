@@ -0,0 +1,124 @@
+// 展开查看：找出一段源代码中所有 #[derive(HelloMacro)] 的类型，
+// 用派生宏本身的代码生成逻辑（hello::impl_hello_macro）生成 impl，
+// 再用 prettyplease 格式化成可读的 Rust 代码。
+//
+// hello_expand 二进制和 tests/expand/ 下的快照测试都基于这里。
+// 生成代码出错时（例如占位符写错），输出中对应位置是一条 compile_error!，
+// 与真实编译时看到的错误一致。
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::{Attribute, DeriveInput, Item, Path, Token};
+
+use crate::hello::impl_hello_macro;
+
+/// 展开整段源代码，返回格式化后的生成代码
+///
+/// 源代码无法解析时返回语法错误；内联模块（`mod zoo { ... }`）中的类型
+/// 会输出在同名模块里，保持原有的层次结构
+pub fn expand_source(source: &str) -> syn::Result<String> {
+    let file = syn::parse_file(source)?;
+    let generated: syn::File = syn::parse2(expand_items(&file.items))?;
+    Ok(prettyplease::unparse(&generated))
+}
+
+fn expand_items(items: &[Item]) -> TokenStream2 {
+    let mut out = TokenStream2::new();
+    for item in items {
+        let input: DeriveInput = match item {
+            Item::Struct(item) if derives_hello(&item.attrs) => item.clone().into(),
+            Item::Enum(item) if derives_hello(&item.attrs) => item.clone().into(),
+            Item::Union(item) if derives_hello(&item.attrs) => item.clone().into(),
+            Item::Mod(module) => {
+                if let Some((_, items)) = &module.content {
+                    let inner = expand_items(items);
+                    if !inner.is_empty() {
+                        let ident = &module.ident;
+                        out.extend(quote! { mod #ident { #inner } });
+                    }
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        out.extend(impl_hello_macro(&input).unwrap_or_else(|err| err.to_compile_error()));
+    }
+    out
+}
+
+/// 属性中是否有 `#[derive(HelloMacro)]`，也接受 `hello_macro::HelloMacro` 这样的路径写法
+fn derives_hello(attrs: &[Attribute]) -> bool {
+    attrs.iter().filter(|attr| attr.path().is_ident("derive")).any(|attr| {
+        attr.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)
+            .map(|paths| {
+                paths
+                    .iter()
+                    .any(|path| path.segments.last().is_some_and(|seg| seg.ident == "HelloMacro"))
+            })
+            .unwrap_or(false)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path as FsPath;
+
+    /// tests/expand/ 下每个 `xxx.rs` 的展开结果必须与 `xxx.expanded.rs` 一致
+    ///
+    /// 修改代码生成后，用 `HELLO_EXPAND=overwrite cargo test` 重新生成快照，
+    /// 再检查 diff 是否符合预期
+    #[test]
+    fn expansions_match_snapshots() {
+        let dir = FsPath::new(env!("CARGO_MANIFEST_DIR")).join("tests/expand");
+        let overwrite = std::env::var_os("HELLO_EXPAND").is_some_and(|v| v == "overwrite");
+        let mut failures = Vec::new();
+
+        let mut inputs: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| {
+                let name = path.file_name().unwrap().to_string_lossy();
+                name.ends_with(".rs") && !name.ends_with(".expanded.rs")
+            })
+            .collect();
+        inputs.sort();
+        assert!(!inputs.is_empty(), "no snapshot inputs in {}", dir.display());
+
+        for input in inputs {
+            let actual = expand_source(&fs::read_to_string(&input).unwrap()).unwrap();
+            let snapshot = input.with_extension("expanded.rs");
+            if overwrite {
+                fs::write(&snapshot, &actual).unwrap();
+                continue;
+            }
+            match fs::read_to_string(&snapshot) {
+                Ok(expected) if expected == actual => {}
+                Ok(expected) => failures.push(format!(
+                    "{} does not match:\n--- expected\n{}\n--- actual\n{}",
+                    snapshot.display(),
+                    expected,
+                    actual
+                )),
+                Err(_) => failures.push(format!("missing snapshot {}", snapshot.display())),
+            }
+        }
+        assert!(
+            failures.is_empty(),
+            "{}\n\nrun `HELLO_EXPAND=overwrite cargo test` to update the snapshots",
+            failures.join("\n\n")
+        );
+    }
+
+    #[test]
+    fn only_hello_macro_derives_are_expanded() {
+        let out = expand_source(
+            "#[derive(Debug)] struct A; #[derive(Clone, hello_macro::HelloMacro)] struct B; fn f() {}",
+        )
+        .unwrap();
+        assert!(!out.contains("for A"), "{}", out);
+        assert!(out.contains("::hello_macro::HelloMacro for B"), "{}", out);
+    }
+}
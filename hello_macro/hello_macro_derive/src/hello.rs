@@ -0,0 +1,216 @@
+// #[derive(HelloMacro)] 的代码生成
+//
+// 只依赖 proc_macro2 / syn / quote，不依赖编译器提供的 proc_macro，
+// 因此除了派生宏本身，展开查看工具 hello_expand 也可以直接复用这里的逻辑。
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{Data, DataEnum, DeriveInput, Member};
+
+use crate::attr::{AttrTarget, HelloAttrs};
+use crate::locale::{locale_match, LOCALES};
+use crate::template::{Placeholder, Template};
+
+/// 类型在某种语言下的问候语和显示名称，枚举的变体在没有单独定制时沿用它们
+struct TypeGreeting {
+    greeting: Template,
+    display_name: TokenStream2,
+}
+
+/// 生成 HelloMacro trait 实现的核心函数
+///
+/// 这个函数负责为给定的结构体或枚举生成 HelloMacro trait 的实现代码。
+///
+/// # 参数
+/// * `ast` - 解析后的抽象语法树，包含结构体/枚举的信息
+///
+/// # 返回值
+/// 返回生成的 impl 代码；输入不受支持或 `#[hello(...)]` 属性有误时
+/// 返回带位置信息的错误
+pub fn impl_hello_macro(ast: &DeriveInput) -> syn::Result<TokenStream2> {
+    // 联合体（union）的字段无法安全读取，直接在 `union` 关键字处报错
+    if let Data::Union(data) = &ast.data {
+        return Err(syn::Error::new_spanned(
+            data.union_token,
+            "`HelloMacro` cannot be derived for unions",
+        ));
+    }
+
+    // 从 AST 中提取结构体/枚举的标识符（名称）
+    // 例如：对于 struct Cat，&ast.ident 就是 "Cat"
+    let name = &ast.ident;
+
+    // 读取 #[hello(...)] 辅助属性，未指定的部分使用默认值
+    let attrs = HelloAttrs::from_attrs(&ast.attrs, AttrTarget::Type)?;
+    // 生成的代码通过完整路径引用 hello_macro，调用方不必导入 trait 也能完成派生
+    let krate = attrs.crate_path();
+
+    // 泛型参数、生命周期、const 泛型和 where 子句原样带到 impl 上，
+    // 再追加 #[hello(bound = "...")] 指定的额外约束
+    let mut generics = ast.generics.clone();
+    if !attrs.bounds.is_empty() {
+        generics.make_where_clause().predicates.extend(attrs.bounds.iter().cloned());
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // 非泛型类型的名称在编译期就已确定，直接写成字符串字面量；
+    // 泛型类型要等到单态化之后才知道具体类型（如 Wrapper<i32>），
+    // 因此在运行时通过 std::any::type_name 获取
+    let type_name = if ast.generics.params.is_empty() {
+        let ident = name.to_string();
+        quote! { #ident }
+    } else {
+        quote! { #krate::TypeName::of::<Self>() }
+    };
+    // 每种语言各生成一份问候语：该语言专属的设置优先，其次是不分语言的
+    // greeting / name，最后是消息目录中该语言的默认问候语
+    let type_greetings: Vec<TypeGreeting> = LOCALES
+        .iter()
+        .map(|locale| TypeGreeting {
+            greeting: attrs
+                .greeting_for(locale)
+                .cloned()
+                .unwrap_or_else(|| Template::default_greeting(locale)),
+            display_name: match attrs.name_for(locale) {
+                Some(lit) => quote! { #lit },
+                None => type_name.clone(),
+            },
+        })
+        .collect();
+
+    // 把模板展开成 write! 的参数：格式字符串 + 每个占位符对应的值
+    // 类型级别的问候语没有具体的值，只能使用 {name} 和 {type}
+    let mut bodies = Vec::new();
+    for tg in &type_greetings {
+        let format_args = tg.greeting.to_format_args("`{name}`, `{type}`", |placeholder| match placeholder {
+            Placeholder::Name => Some(tg.display_name.clone()),
+            Placeholder::Type => Some(type_name.clone()),
+            Placeholder::Variant | Placeholder::Field(_) => None,
+        })?;
+        bodies.push(quote! { ::core::write!(__f, #format_args) });
+    }
+    // 运行时按 hello_macro::locale::current() 选择语言
+    let greeting_body = locale_match(&krate, bodies);
+
+    // 枚举需要知道当前是哪个变体，因此额外生成按变体 match 的 write_hello(&self)；
+    // 结构体直接使用 trait 中的默认实现（转发给 write_greeting）
+    // 泛型参数命名为 __W，避免与类型自身的泛型参数重名
+    let hello_fn = match &ast.data {
+        Data::Enum(data) => {
+            let body = enum_hello_body(data, &krate, &type_greetings, &type_name)?;
+            quote! {
+                fn write_hello<__W: ::core::fmt::Write + ?Sized>(&self, __f: &mut __W) -> ::core::fmt::Result {
+                    #body
+                }
+            }
+        }
+        _ => quote! {},
+    };
+
+    // 把非泛型类型登记到 hello_macro::registry()，以便运行时按名称列出和调用；
+    // 泛型类型没有唯一的具体类型，无法登记
+    let register = if ast.generics.params.is_empty() {
+        let ident = name.to_string();
+        quote! { #krate::__register!(#name, #ident); }
+    } else {
+        quote! {}
+    };
+
+    // 使用 quote! 宏生成 Rust 代码
+    // quote! 允许我们在代码中使用模板语法 #{} 来插入变量
+    let gen = quote! {
+        // 为指定的结构体实现 HelloMacro trait
+        impl #impl_generics #krate::HelloMacro for #name #ty_generics #where_clause {
+            // 实现 write_greeting 方法，hello_macro() 等其余方法由 trait 的默认实现提供
+            fn write_greeting<__W: ::core::fmt::Write + ?Sized>(__f: &mut __W) -> ::core::fmt::Result {
+                // 写入问候消息，默认为 "Hello, Macro! I'm a Cat!"（中文为 "你好，宏！我是Cat！"）
+                #greeting_body
+            }
+
+            #hello_fn
+        }
+
+        #register
+    };
+
+    Ok(gen)
+}
+
+/// 为枚举生成 `write_hello(&self, __f)` 的函数体：对每个变体生成一个 match 分支
+///
+/// 变体上可以用 `#[hello(greeting = "...", name = "...")]` 单独定制问候语，
+/// 也可以用 `#[hello(zh(...))]` 按语言定制，
+/// 模板中除了 `{name}` 和 `{type}`，还可以使用 `{variant}` 以及变体的字段，
+/// 例如 `{radius}`（具名字段）或 `{0}`（元组字段）。
+/// 没有单独定制的变体沿用类型上的问候语，此时 `{name}` 默认为 `类型名::变体名`。
+fn enum_hello_body(
+    data: &DataEnum,
+    krate: &syn::Path,
+    type_greetings: &[TypeGreeting],
+    type_name: &TokenStream2,
+) -> syn::Result<TokenStream2> {
+    let mut arms = Vec::new();
+    for variant in &data.variants {
+        let attrs = HelloAttrs::from_attrs(&variant.attrs, AttrTarget::Variant)?;
+        let ident = &variant.ident;
+        let variant_str = ident.to_string();
+
+        // 只绑定模板中实际用到的字段，其余字段用 `..` 忽略，避免未使用变量的警告
+        // `Self::Variant { 0: a, .. }` 这种写法对元组变体同样有效
+        // 各语言的模板共用同一个模式，绑定的是所有语言用到的字段的并集
+        let mut bindings: Vec<(Member, syn::Ident)> = Vec::new();
+        let mut bodies = Vec::new();
+        for (locale, tg) in LOCALES.iter().zip(type_greetings) {
+            let variant_name = match attrs.name_for(locale) {
+                Some(lit) => quote! { #lit },
+                None => {
+                    let display_name = &tg.display_name;
+                    quote! { ::core::format_args!("{}::{}", #display_name, #variant_str) }
+                }
+            };
+            let greeting = attrs.greeting_for(locale).unwrap_or(&tg.greeting);
+
+            let expected = format!("`{{name}}`, `{{type}}`, `{{variant}}` or a field of `{}`", variant_str);
+            let format_args = greeting.to_format_args(&expected, |placeholder| match placeholder {
+                Placeholder::Name => Some(variant_name.clone()),
+                Placeholder::Type => Some(type_name.clone()),
+                Placeholder::Variant => Some(quote! { #variant_str }),
+                Placeholder::Field(member) => {
+                    let exists = variant.fields.iter().enumerate().any(|(i, field)| match (member, &field.ident) {
+                        (Member::Named(name), Some(field_name)) => name == field_name,
+                        (Member::Unnamed(index), None) => index.index as usize == i,
+                        _ => false,
+                    });
+                    if !exists {
+                        return None;
+                    }
+                    let binding = match member {
+                        Member::Named(name) => format_ident!("__hello_{}", name),
+                        Member::Unnamed(index) => format_ident!("__hello_{}", index.index),
+                    };
+                    if !bindings.iter().any(|(m, _)| m == member) {
+                        bindings.push((member.clone(), binding.clone()));
+                    }
+                    Some(quote! { #binding })
+                }
+            })?;
+            bodies.push(quote! { ::core::write!(__f, #format_args) });
+        }
+
+        let patterns = bindings.iter().map(|(member, binding)| quote! { #member: #binding });
+        let body = locale_match(krate, bodies);
+        arms.push(quote! {
+            Self::#ident { #(#patterns,)* .. } => #body,
+        });
+    }
+
+    // 没有变体的枚举不存在任何值，match 可以为空
+    if arms.is_empty() {
+        return Ok(quote! { match *self {} });
+    }
+    Ok(quote! {
+        match self {
+            #(#arms)*
+        }
+    })
+}
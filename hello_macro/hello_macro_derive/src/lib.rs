@@ -1,7 +1,17 @@
 // 引入过程宏所需的核心库
 use proc_macro::TokenStream;  // 编译器提供的 TokenStream 类型
-use syn::DeriveInput;         // syn 库提供的派生输入结构体
-use quote::quote;             // quote 库提供的 quote! 宏
+use syn::{parse_macro_input, DeriveInput, ItemFn};  // syn 库提供的派生输入结构体
+
+mod attr;      // #[hello(...)] 辅助属性解析
+#[cfg(test)]
+mod expand;    // 展开查看工具 hello_expand 的核心逻辑，这里只为运行快照测试
+mod hello;     // #[derive(HelloMacro)] 的代码生成
+mod locale;    // 内置的消息目录：每种语言的默认问候语
+mod reflect;   // #[derive(Reflect)] 的代码生成
+mod template;  // 问候语模板与占位符校验
+mod trace;     // #[hello_fn] 属性宏的代码生成
+
+use hello::impl_hello_macro;
 
 /// HelloMacro 自定义派生宏的入口函数
 ///
@@ -13,49 +23,86 @@ use quote::quote;             // quote 库提供的 quote! 宏
 ///
 /// # 返回值
 /// 返回生成的 impl 代码的 TokenStream
-#[proc_macro_derive(HelloMacro)]
+///
+/// # 辅助属性
+/// `attributes(hello)` 声明了 `#[hello(...)]` 辅助属性，用于自定义问候语：
+/// * `greeting = "..."` - 问候语模板，支持 `{name}` 与 `{type}` 占位符
+/// * `name = "..."` - 覆盖 `{name}` 的显示名称（默认为类型名）
+/// * `bound = "..."` - 为生成的 impl 追加 where 约束，例如 `"T: Clone"`
+/// * `crate = "..."` - hello_macro 的路径，供重新导出它的下游 crate 使用（默认 `::hello_macro`）
+/// * `zh(greeting = "...", name = "...")` / `en(...)` - 只在对应语言下生效的问候语和名称，
+///   运行时按 `hello_macro::locale::current()` 选择，未定制的语言使用内置的默认问候语
+#[proc_macro_derive(HelloMacro, attributes(hello))]
 pub fn hello_macro_derive(input: TokenStream) -> TokenStream {
     // 步骤1: 解析输入的 TokenStream 为抽象语法树 (AST)
-    // syn::parse 将原始的 TokenStream 转换为结构化的 DeriveInput
-    let ast = syn::parse(input).unwrap();
+    // parse_macro_input! 解析失败时会直接返回 compile_error!，而不是 panic
+    let ast = parse_macro_input!(input as DeriveInput);
 
     // 步骤2: 根据解析得到的 AST 生成实现代码
-    let gen = impl_hello_macro(&ast);
+    // 出错时返回带 span 的 compile_error!，让编译器在出错的位置报告原因
+    let gen = impl_hello_macro(&ast).unwrap_or_else(|err| err.to_compile_error());
 
     // 步骤3: 将生成的代码转换为 TokenStream 返回给编译器
     gen.into()
 }
 
-/// 生成 HelloMacro trait 实现的核心函数
+/// Reflect 自定义派生宏的入口函数
 ///
-/// 这个函数负责为给定的结构体或枚举生成 HelloMacro trait 的实现代码。
+/// 为类型生成 `hello_macro::Reflect` 的实现，把类型名、种类、
+/// 字段名、字段类型和变体列表写成编译期常量 `TYPE_INFO`。
 ///
-/// # 参数
-/// * `ast` - 解析后的抽象语法树，包含结构体/枚举的信息
+/// # 示例
+/// ```rust
+/// use hello_macro::Reflect;
+/// use hello_macro_derive::Reflect;
 ///
-/// # 返回值
-/// 返回生成的 impl 代码的 TokenStream
-fn impl_hello_macro(ast: &DeriveInput) -> TokenStream {
-    // 从 AST 中提取结构体/枚举的标识符（名称）
-    // 例如：对于 struct Cat，&ast.ident 就是 "Cat"
-    let name = &ast.ident;
-
-    // 使用 quote! 宏生成 Rust 代码
-    // quote! 允许我们在代码中使用模板语法 #{} 来插入变量
-    let gen = quote! {
-        // 为指定的结构体实现 HelloMacro trait
-        impl HelloMacro for #name {
-            // 实现 hello_macro 方法
-            fn hello_macro() {
-                // 打印包含结构体名称的问候消息
-                // stringify!(#name) 将标识符转换为字符串字面量
-                println!("Hello, Macro! I'm a {}!", stringify!(#name));
-            }
-        }
-    };
+/// #[derive(Reflect)]
+/// struct Cat { name: String, age: u8 }
+///
+/// assert_eq!(Cat::TYPE_INFO.to_string(), "struct Cat { name: String, age: u8 }");
+/// ```
+#[proc_macro_derive(Reflect, attributes(hello))]
+pub fn reflect_derive(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    reflect::impl_reflect(&ast)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
 
-    // 将生成的代码转换为 TokenStream 并返回
-    gen.into()
+/// hello_fn 属性宏的入口函数
+///
+/// 与派生宏不同，属性宏会替换掉它所标注的条目：这里把函数体包裹起来，
+/// 在进入时记录参数，在返回时记录返回值和耗时，输出到标准错误。
+/// 普通函数、方法和 `async fn` 都可以使用；没有实现 `Debug` 的参数和返回值显示为 `_`。
+///
+/// # 参数
+/// * `name = "..."` - 输出中显示的名称（默认为函数名）
+/// * `skip(a, b)` - 不记录这些参数的值，例如密码
+/// * `crate = "..."` - hello_macro 的路径（默认 `::hello_macro`）
+///
+/// # 示例
+/// ```rust
+/// use hello_macro_derive::hello_fn;
+///
+/// #[hello_fn]
+/// fn add(a: i32, b: i32) -> i32 {
+///     a + b
+/// }
+///
+/// // 标准错误输出：
+/// // [hello_fn] → add(a = 1, b = 2)
+/// // [hello_fn] ← add = 3 (1.2µs)
+/// assert_eq!(add(1, 2), 3);
+/// ```
+#[proc_macro_attribute]
+pub fn hello_fn(args: TokenStream, input: TokenStream) -> TokenStream {
+    let mut options = trace::TraceOptions::default();
+    let parser = syn::meta::parser(|meta| options.parse(meta));
+    parse_macro_input!(args with parser);
+    let item = parse_macro_input!(input as ItemFn);
+    trace::impl_hello_fn(&options, item)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
 }
 
 /*
@@ -67,12 +114,21 @@ fn impl_hello_macro(ast: &DeriveInput) -> TokenStream {
    ↓
 3. 代码解析：syn::parse 将 "struct Cat;" 转换为 DeriveInput
    ↓
-4. 代码生成：impl_hello_macro 生成以下代码：
-   impl HelloMacro for Cat {
-       fn hello_macro() {
-           println!("Hello, Macro! I'm a Cat!");
+4. 代码生成：impl_hello_macro（src/hello.rs）生成以下代码：
+   impl ::hello_macro::HelloMacro for Cat {
+       fn write_greeting<__W: ::core::fmt::Write + ?Sized>(__f: &mut __W) -> ::core::fmt::Result {
+           match ::hello_macro::locale::current() {
+               ::hello_macro::locale::Locale::En => ::core::write!(__f, "Hello, Macro! I'm a {}!", "Cat"),
+               ::hello_macro::locale::Locale::Zh => ::core::write!(__f, "你好，宏！我是{}！", "Cat"),
+           }
        }
    }
+   ::hello_macro::__register!(Cat, "Cat");
+
+   hello_macro()、greeting() 等方法都由 trait 的默认实现基于它提供；
+   想查看任意代码的真实展开结果，可以运行：
+       cargo run --features inspect --bin hello_expand -- path/to/file.rs
+   tests/expand/ 中的快照固定了常见写法的展开结果
    ↓
 5. 编译继续：编译器将生成的代码插入到原位置，继续编译
 
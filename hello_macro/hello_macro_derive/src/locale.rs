@@ -0,0 +1,77 @@
+// 内置的消息目录：每种语言的默认问候语
+//
+// 必须与 hello_macro::locale::Locale 的变体一一对应。生成的代码会对 Locale 做穷尽匹配，
+// 两边不一致时会在使用派生宏的地方直接编译失败，而不是悄悄退回某种语言。
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::Path;
+
+/// 一种语言
+pub struct LocaleDef {
+    /// `#[hello(zh(...))]` 中的键，与 `Locale::code()` 相同
+    pub key: &'static str,
+    /// `hello_macro::locale::Locale` 中的变体名
+    pub variant: &'static str,
+    /// 默认问候语模板
+    pub greeting: &'static str,
+}
+
+/// 所有支持的语言，顺序与 `Locale::ALL` 一致
+pub const LOCALES: &[LocaleDef] = &[
+    LocaleDef { key: "en", variant: "En", greeting: "Hello, Macro! I'm a {name}!" },
+    LocaleDef { key: "zh", variant: "Zh", greeting: "你好，宏！我是{name}！" },
+];
+
+/// 按键查找语言
+pub fn find(key: &str) -> Option<&'static LocaleDef> {
+    LOCALES.iter().find(|locale| locale.key == key)
+}
+
+/// 错误提示中列出的语言键，例如 "`en`, `zh`"
+pub fn keys() -> String {
+    LOCALES.iter().map(|locale| format!("`{}`", locale.key)).collect::<Vec<_>>().join(", ")
+}
+
+/// 根据运行时的语言选择一段代码，`bodies` 与 [`LOCALES`] 一一对应
+///
+/// 内容相同的分支合并成 `Locale::En | Locale::Zh => ...`；
+/// 所有语言都相同时（例如只写了不分语言的 `greeting`）直接返回这段代码，不生成 match
+pub fn locale_match(krate: &Path, bodies: Vec<TokenStream2>) -> TokenStream2 {
+    let mut groups: Vec<(Vec<&LocaleDef>, String, TokenStream2)> = Vec::new();
+    for (locale, body) in LOCALES.iter().zip(bodies) {
+        let key = body.to_string();
+        match groups.iter_mut().find(|(_, k, _)| *k == key) {
+            Some((locales, _, _)) => locales.push(locale),
+            None => groups.push((vec![locale], key, body)),
+        }
+    }
+    if groups.len() == 1 {
+        return groups.pop().map(|(_, _, body)| body).unwrap_or_default();
+    }
+    let arms = groups.into_iter().map(|(locales, _, body)| {
+        let variants = locales.iter().map(|locale| format_ident!("{}", locale.variant));
+        quote! { #(#krate::locale::Locale::#variants)|* => #body, }
+    });
+    quote! {
+        match #krate::locale::current() {
+            #(#arms)*
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_bodies_are_merged() {
+        let krate: Path = syn::parse_quote!(::hello_macro);
+        let same = locale_match(&krate, vec![quote!(a()), quote!(a())]);
+        assert_eq!(same.to_string(), "a ()");
+
+        let split = locale_match(&krate, vec![quote!(a()), quote!(b())]).to_string();
+        assert!(split.contains("Locale :: En => a ()"), "{}", split);
+        assert!(split.contains("Locale :: Zh => b ()"), "{}", split);
+    }
+}
@@ -0,0 +1,162 @@
+// #[derive(Reflect)] 的代码生成
+//
+// 把 DeriveInput 中的结构信息（类型名、种类、字段、变体）原样写成
+// hello_macro::TypeInfo 常量，例如：
+//
+//     #[derive(Reflect)]
+//     struct Cat { name: String, age: u8 }
+//
+// 会生成：
+//
+//     impl ::hello_macro::Reflect for Cat {
+//         const TYPE_INFO: ::hello_macro::TypeInfo = ::hello_macro::TypeInfo {
+//             name: "Cat",
+//             kind: ::hello_macro::Kind::Struct,
+//             fields: &[
+//                 ::hello_macro::FieldInfo { name: "name", ty: "String" },
+//                 ::hello_macro::FieldInfo { name: "age", ty: "u8" },
+//             ],
+//             variants: &[],
+//         };
+//     }
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, ToTokens};
+use syn::{Data, DeriveInput, Fields, Path};
+
+use crate::attr::{AttrTarget, HelloAttrs};
+
+/// 生成 Reflect trait 的实现
+///
+/// 与 HelloMacro 共用 `#[hello(...)]` 属性，其中的 `crate = "..."` 决定生成代码引用的路径
+pub fn impl_reflect(ast: &DeriveInput) -> syn::Result<TokenStream2> {
+    let krate = HelloAttrs::from_attrs(&ast.attrs, AttrTarget::Type)?.crate_path();
+    let name = &ast.ident;
+    let name_str = name.to_string();
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let (kind, fields, variants) = match &ast.data {
+        Data::Struct(data) => {
+            let kind = match &data.fields {
+                Fields::Named(_) => quote! { #krate::Kind::Struct },
+                Fields::Unnamed(_) => quote! { #krate::Kind::TupleStruct },
+                Fields::Unit => quote! { #krate::Kind::UnitStruct },
+            };
+            (kind, field_infos(&krate, &data.fields), Vec::new())
+        }
+        Data::Enum(data) => {
+            let variants = data
+                .variants
+                .iter()
+                .map(|variant| {
+                    let variant_name = variant.ident.to_string();
+                    let kind = match &variant.fields {
+                        Fields::Named(_) => quote! { #krate::VariantKind::Struct },
+                        Fields::Unnamed(_) => quote! { #krate::VariantKind::Tuple },
+                        Fields::Unit => quote! { #krate::VariantKind::Unit },
+                    };
+                    let fields = field_infos(&krate, &variant.fields);
+                    quote! {
+                        #krate::VariantInfo {
+                            name: #variant_name,
+                            kind: #kind,
+                            fields: &[#(#fields),*],
+                        }
+                    }
+                })
+                .collect();
+            (quote! { #krate::Kind::Enum }, Vec::new(), variants)
+        }
+        Data::Union(data) => {
+            return Err(syn::Error::new_spanned(
+                data.union_token,
+                "`Reflect` cannot be derived for unions",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics #krate::Reflect for #name #ty_generics #where_clause {
+            const TYPE_INFO: #krate::TypeInfo = #krate::TypeInfo {
+                name: #name_str,
+                kind: #kind,
+                fields: &[#(#fields),*],
+                variants: &[#(#variants),*],
+            };
+        }
+    })
+}
+
+/// 为每个字段生成一个 FieldInfo 表达式；元组字段以下标作为名称
+fn field_infos(krate: &Path, fields: &Fields) -> Vec<TokenStream2> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let name = match &field.ident {
+                Some(ident) => ident.to_string().trim_start_matches("r#").to_string(),
+                None => i.to_string(),
+            };
+            let ty = type_string(&field.ty);
+            quote! { #krate::FieldInfo { name: #name, ty: #ty } }
+        })
+        .collect()
+}
+
+/// 把类型转换成接近源代码书写习惯的字符串
+///
+/// TokenStream 的 to_string() 会在每个 token 之间插入空格，
+/// 例如 `Vec < Option < & 'a str > >`，这里把多余的空格去掉，得到 `Vec<Option<&'a str>>`
+fn type_string(ty: &syn::Type) -> String {
+    tidy_tokens(&ty.to_token_stream().to_string())
+}
+
+fn tidy_tokens(raw: &str) -> String {
+    let mut out = String::new();
+    let mut prev = "";
+    for token in raw.split_whitespace() {
+        if !prev.is_empty() && needs_space(prev, token) {
+            out.push(' ');
+        }
+        out.push_str(token);
+        prev = token;
+    }
+    out
+}
+
+/// 两个相邻 token 之间是否需要保留空格
+fn needs_space(prev: &str, next: &str) -> bool {
+    let last = prev.chars().last().unwrap_or(' ');
+    let first = next.chars().next().unwrap_or(' ');
+    if matches!(last, '&' | '<' | '(' | '[') || prev.ends_with("::") || prev == "*" {
+        return false;
+    }
+    if matches!(first, '>' | ',' | ')' | ']' | ';') || next.starts_with("::") {
+        return false;
+    }
+    // `Vec <`、`Fn (` 这类紧跟在名称后面的括号不需要空格，但 `-> (` 需要
+    if matches!(first, '<' | '(') {
+        return !(last.is_alphanumeric() || last == '_' || last == '>') || prev == "->";
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ty(source: &str) -> String {
+        type_string(&syn::parse_str(source).unwrap())
+    }
+
+    #[test]
+    fn tidies_common_types() {
+        assert_eq!(ty("Vec<Option<&'a str>>"), "Vec<Option<&'a str>>");
+        assert_eq!(ty("std::collections::HashMap<String, Vec<u8>>"), "std::collections::HashMap<String, Vec<u8>>");
+        assert_eq!(ty("[u8; N]"), "[u8; N]");
+        assert_eq!(ty("(f64, f64)"), "(f64, f64)");
+        assert_eq!(ty("&mut T"), "&mut T");
+        assert_eq!(ty("*const u8"), "*const u8");
+        assert_eq!(ty("Box<dyn Fn(i32) -> (i32, i32) + Send>"), "Box<dyn Fn(i32) -> (i32, i32) + Send>");
+    }
+}
@@ -0,0 +1,236 @@
+// 问候语模板：解析 `#[hello(greeting = "...")]` 中的字符串
+//
+// 模板是普通文本加上 `{name}` 风格的占位符，例如：
+//
+//     "喵～ 我是 {name}（{type}）"
+//     "{variant} 的半径是 {radius:.1}"
+//
+// 占位符在宏展开时就会被校验，写错的占位符会直接变成编译错误，
+// 而不是等到运行时才发现输出不对。
+// `{{` 和 `}}` 分别表示字面量的 `{` 和 `}`，与 format! 的规则一致；
+// 冒号之后的部分是格式说明（如 `:?`、`:.2`），原样交给 format!。
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{Ident, Index, LitStr, Member};
+
+use crate::locale::LocaleDef;
+
+/// 模板中可以使用的占位符
+#[derive(Clone, PartialEq, Eq)]
+pub enum Placeholder {
+    /// `{name}`：显示名称，可通过 `#[hello(name = "...")]` 覆盖
+    Name,
+    /// `{type}`：Rust 中的类型名，不受 `name` 覆盖影响
+    Type,
+    /// `{variant}`：枚举变体名，只能用在变体上的 `#[hello(...)]` 中
+    Variant,
+    /// `{radius}`、`{0}`：变体字段的值，只能用在变体上的 `#[hello(...)]` 中
+    Field(Member),
+}
+
+impl Placeholder {
+    fn parse(key: &str, span: Span) -> Option<Self> {
+        match key {
+            "name" => Some(Placeholder::Name),
+            "type" => Some(Placeholder::Type),
+            "variant" => Some(Placeholder::Variant),
+            _ => {
+                if let Ok(index) = key.parse::<u32>() {
+                    Some(Placeholder::Field(Member::Unnamed(Index { index, span })))
+                } else {
+                    syn::parse_str::<Ident>(key).ok().map(|mut ident| {
+                        ident.set_span(span);
+                        Placeholder::Field(Member::Named(ident))
+                    })
+                }
+            }
+        }
+    }
+}
+
+/// 模板片段：要么是原样输出的文本，要么是一个占位符及其格式说明
+#[derive(Clone, PartialEq, Eq)]
+enum Segment {
+    Text(String),
+    Placeholder { key: String, placeholder: Placeholder, spec: String },
+}
+
+/// 解析后的问候语模板
+#[derive(Clone)]
+pub struct Template {
+    segments: Vec<Segment>,
+    /// 模板字面量的位置，校验占位符失败时错误指向这里
+    span: Span,
+}
+
+impl Template {
+    /// 消息目录中某种语言的默认模板；英文版与最初版本的输出保持一致
+    pub fn default_greeting(locale: &LocaleDef) -> Self {
+        Self::parse_str(locale.greeting, Span::call_site())
+            .expect("built-in greetings are valid templates")
+    }
+
+    /// 解析字符串字面量，错误会指向该字面量
+    pub fn parse(lit: &LitStr) -> syn::Result<Self> {
+        Self::parse_str(&lit.value(), lit.span()).map_err(|msg| syn::Error::new(lit.span(), msg))
+    }
+
+    fn parse_str(source: &str, span: Span) -> Result<Self, String> {
+        let mut segments = Vec::new();
+        let mut text = String::new();
+        let mut chars = source.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut inner = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => inner.push(c),
+                            None => {
+                                return Err(format!(
+                                    "unclosed placeholder `{{{}` in greeting; use `{{{{` for a literal `{{`",
+                                    inner
+                                ))
+                            }
+                        }
+                    }
+                    let (key, spec) = match inner.split_once(':') {
+                        Some((key, spec)) => (key.trim(), format!(":{}", spec)),
+                        None => (inner.trim(), String::new()),
+                    };
+                    let placeholder = Placeholder::parse(key, span).ok_or_else(|| {
+                        format!("invalid placeholder `{{{}}}` in greeting", inner)
+                    })?;
+                    if !text.is_empty() {
+                        segments.push(Segment::Text(std::mem::take(&mut text)));
+                    }
+                    segments.push(Segment::Placeholder { key: key.to_string(), placeholder, spec });
+                }
+                '}' => {
+                    return Err(
+                        "unmatched `}` in greeting; use `}}` for a literal `}`".to_string()
+                    )
+                }
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            segments.push(Segment::Text(text));
+        }
+        Ok(Self { segments, span })
+    }
+
+    /// 生成 `format!` 系列宏的参数列表：格式字符串加上按顺序排列的实参
+    ///
+    /// `resolve` 负责把每个占位符翻译成一个表达式；当前上下文中不可用的占位符
+    /// 返回 `None`，此时会报告编译错误，`expected` 用于错误提示中列出可用的占位符
+    pub fn to_format_args<F>(&self, expected: &str, mut resolve: F) -> syn::Result<TokenStream2>
+    where
+        F: FnMut(&Placeholder) -> Option<TokenStream2>,
+    {
+        let mut fmt = String::new();
+        let mut args = Vec::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(text) => fmt.push_str(&text.replace('{', "{{").replace('}', "}}")),
+                Segment::Placeholder { key, placeholder, spec } => {
+                    let arg = resolve(placeholder).ok_or_else(|| {
+                        syn::Error::new(
+                            self.span,
+                            format!(
+                                "unknown placeholder `{{{}}}` in greeting; expected one of {}",
+                                key, expected
+                            ),
+                        )
+                    })?;
+                    fmt.push('{');
+                    fmt.push_str(spec);
+                    fmt.push('}');
+                    args.push(arg);
+                }
+            }
+        }
+        Ok(quote! { #fmt #(, #args)* })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> Result<Template, String> {
+        Template::parse_str(source, Span::call_site())
+    }
+
+    fn placeholders(t: &Template) -> Vec<(Placeholder, String)> {
+        t.segments
+            .iter()
+            .filter_map(|s| match s {
+                Segment::Placeholder { placeholder, spec, .. } => Some((placeholder.clone(), spec.clone())),
+                Segment::Text(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_text_and_placeholders() {
+        let t = parse("Hi {name}, a {type} / {variant}!").unwrap();
+        assert_eq!(t.segments.len(), 7);
+        assert!(
+            placeholders(&t)
+                == vec![
+                    (Placeholder::Name, String::new()),
+                    (Placeholder::Type, String::new()),
+                    (Placeholder::Variant, String::new()),
+                ]
+        );
+    }
+
+    #[test]
+    fn parses_fields_and_format_specs() {
+        let t = parse("{radius:.2} {0:?}").unwrap();
+        let found = placeholders(&t);
+        assert!(matches!(&found[0], (Placeholder::Field(Member::Named(id)), spec) if id == "radius" && spec == ":.2"));
+        assert!(matches!(&found[1], (Placeholder::Field(Member::Unnamed(i)), spec) if i.index == 0 && spec == ":?"));
+    }
+
+    #[test]
+    fn built_in_greetings_use_name() {
+        for locale in crate::locale::LOCALES {
+            let t = Template::default_greeting(locale);
+            assert!(placeholders(&t) == vec![(Placeholder::Name, String::new())], "{}", locale.key);
+        }
+    }
+
+    #[test]
+    fn escaped_braces_are_text() {
+        let t = parse("{{name}}").unwrap();
+        assert!(t.segments == vec![Segment::Text("{name}".into())]);
+    }
+
+    #[test]
+    fn rejects_invalid_and_unbalanced() {
+        let err = parse("{not valid}").err().unwrap();
+        assert!(err.contains("invalid placeholder `{not valid}`"), "{}", err);
+        assert!(parse("oops {name").is_err());
+        assert!(parse("oops }").is_err());
+    }
+
+    #[test]
+    fn unresolved_placeholder_is_an_error() {
+        let t = parse("{nmae}").unwrap();
+        let err = t.to_format_args("`{name}`", |_| None).err().unwrap();
+        assert!(err.to_string().contains("unknown placeholder `{nmae}`"), "{}", err);
+    }
+}
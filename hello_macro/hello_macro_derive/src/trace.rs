@@ -0,0 +1,210 @@
+// #[hello_fn] 的代码生成
+//
+// 属性宏把函数体包裹起来，在进入时记录参数、在返回时记录返回值和耗时：
+//
+//     #[hello_fn]
+//     fn add(a: i32, b: i32) -> i32 { a + b }
+//
+// 会生成（省略了部分路径）：
+//
+//     fn add(a: i32, b: i32) -> i32 {
+//         let __hello_call = {
+//             use ::hello_macro::trace::{ViaDebug as _, ViaOpaque as _};
+//             ::hello_macro::trace::Call::enter("add", &[
+//                 ("a", (&::hello_macro::trace::DebugArg(&a)).__hello_debug()),
+//                 ("b", (&::hello_macro::trace::DebugArg(&b)).__hello_debug()),
+//             ])
+//         };
+//         let __hello_ret: i32 = {
+//             fn __hello_once<R, F: FnOnce() -> R>(f: F) -> R { f() }
+//             __hello_once(move || { a + b })
+//         };
+//         { /* __hello_call.exit(...) */ }
+//         __hello_ret
+//     }
+//
+// 原函数体放进闭包（`async fn` 则放进 `async move` 块）里执行，
+// 这样函数体中的 `return` 和 `?` 只会离开闭包，返回值仍然能被记录下来。
+//
+// 闭包通过 `__hello_once` 调用，被推断为 FnOnce：捕获的 `self` 随调用一起交出，
+// 函数体才能返回从 `&mut self` / `&self` 借出的引用（例如 `&mut self.items`）。
+// 直接写 `(move || body)()` 时闭包会被推断为 FnMut，这样的引用无法离开闭包。
+
+use proc_macro2::{TokenStream as TokenStream2, TokenTree};
+use quote::{quote, ToTokens};
+use syn::ext::IdentExt;
+use syn::meta::ParseNestedMeta;
+use syn::punctuated::Punctuated;
+use syn::{FnArg, Ident, ItemFn, LitStr, Pat, Path, ReturnType, Token, Type};
+
+/// 从 `#[hello_fn(...)]` 中收集到的配置
+#[derive(Default)]
+pub struct TraceOptions {
+    /// 输出中显示的函数名，默认为函数本身的名字
+    name: Option<LitStr>,
+    /// 不记录值的参数，例如密码或体积很大的参数
+    skip: Vec<Ident>,
+    /// hello_macro crate 的路径，默认为 `::hello_macro`
+    krate: Option<Path>,
+}
+
+impl TraceOptions {
+    /// 解析 `#[hello_fn(...)]` 中的一项，配合 `syn::meta::parser` 使用
+    ///
+    /// 支持 `name = "..."`、`skip(a, b)` 和 `crate = "..."`
+    pub fn parse(&mut self, meta: ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("name") {
+            if self.name.is_some() {
+                return Err(meta.error("duplicate `name` in #[hello_fn(...)]"));
+            }
+            self.name = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("skip") {
+            let content;
+            syn::parenthesized!(content in meta.input);
+            let idents = Punctuated::<Ident, Token![,]>::parse_terminated_with(&content, Ident::parse_any)?;
+            self.skip.extend(idents);
+        } else if meta.path.is_ident("crate") {
+            if self.krate.is_some() {
+                return Err(meta.error("duplicate `crate` in #[hello_fn(...)]"));
+            }
+            self.krate = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+        } else {
+            let key = meta.path.to_token_stream().to_string().replace(' ', "");
+            return Err(meta.error(format!(
+                "unknown hello_fn option `{}`; expected one of `name`, `skip`, `crate`",
+                key
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// 生成包裹了跟踪代码的函数
+pub fn impl_hello_fn(options: &TraceOptions, item: ItemFn) -> syn::Result<TokenStream2> {
+    // const fn 中无法计时，也无法输出
+    if let Some(constness) = &item.sig.constness {
+        return Err(syn::Error::new_spanned(
+            constness,
+            "`#[hello_fn]` cannot be used on a `const fn`",
+        ));
+    }
+
+    let krate = options
+        .krate
+        .clone()
+        .unwrap_or_else(|| syn::parse_quote!(::hello_macro));
+    let fn_name = match &options.name {
+        Some(lit) => lit.value(),
+        None => item.sig.ident.unraw().to_string(),
+    };
+
+    // 收集要记录的参数：`self` 和简单的 `name: Type` 参数；
+    // 解构模式（如 `(a, b): (i32, i32)`）没有单一的名字，不记录
+    let mut args = Vec::new();
+    for input in &item.sig.inputs {
+        match input {
+            FnArg::Receiver(receiver) => {
+                let token = &receiver.self_token;
+                args.push((Ident::new("self", token.span), quote! { #token }));
+            }
+            FnArg::Typed(pat_type) => {
+                if let Pat::Ident(pat) = &*pat_type.pat {
+                    if pat.subpat.is_none() {
+                        let ident = &pat.ident;
+                        args.push((ident.unraw(), quote! { #ident }));
+                    }
+                }
+            }
+        }
+    }
+    for skipped in &options.skip {
+        if !args.iter().any(|(name, _)| name == skipped) {
+            return Err(syn::Error::new_spanned(
+                skipped,
+                format!("`{}` is not an argument of `{}`", skipped, item.sig.ident),
+            ));
+        }
+    }
+    let args = args
+        .iter()
+        .filter(|(name, _)| !options.skip.contains(name))
+        .map(|(name, expr)| {
+            let name = name.to_string();
+            quote! { (#name, (&#krate::trace::DebugArg(&#expr)).__hello_debug()) }
+        });
+
+    // 返回类型写在 let 上，让函数体中的 `?` 能推断出错误类型；
+    // `impl Trait` 和 `!` 不能出现在 let 的类型标注中，这时交给编译器推断
+    let ret_annotation = match &item.sig.output {
+        ReturnType::Type(_, ty) if !matches!(**ty, Type::Never(_)) && !mentions_impl(ty.to_token_stream()) => {
+            quote! { : #ty }
+        }
+        ReturnType::Type(..) => quote! {},
+        ReturnType::Default => quote! { : () },
+    };
+
+    let block = &item.block;
+    let run = if item.sig.asyncness.is_some() {
+        quote! { async move #block.await }
+    } else {
+        quote! {{
+            fn __hello_once<R, F: FnOnce() -> R>(f: F) -> R {
+                f()
+            }
+            __hello_once(move || #block)
+        }}
+    };
+
+    let ItemFn { attrs, vis, sig, .. } = &item;
+    Ok(quote! {
+        #(#attrs)*
+        #vis #sig {
+            let __hello_call = {
+                #[allow(unused_imports)]
+                use #krate::trace::{ViaDebug as _, ViaOpaque as _};
+                #krate::trace::Call::enter(#fn_name, &[#(#args),*])
+            };
+            #[allow(clippy::let_unit_value)]
+            let __hello_ret #ret_annotation = #run;
+            {
+                #[allow(unused_imports)]
+                use #krate::trace::{ViaDebug as _, ViaOpaque as _};
+                __hello_call.exit((&#krate::trace::DebugArg(&__hello_ret)).__hello_debug());
+            }
+            __hello_ret
+        }
+    })
+}
+
+/// 类型中是否出现了 `impl Trait`
+fn mentions_impl(tokens: TokenStream2) -> bool {
+    tokens.into_iter().any(|tt| match tt {
+        TokenTree::Ident(ident) => ident == "impl",
+        TokenTree::Group(group) => mentions_impl(group.stream()),
+        _ => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_impl_trait_anywhere_in_the_type() {
+        assert!(mentions_impl(quote! { impl Iterator<Item = u8> }));
+        assert!(mentions_impl(quote! { Box<(u8, impl Fn())> }));
+        assert!(!mentions_impl(quote! { Result<Vec<u8>, String> }));
+    }
+
+    #[test]
+    fn rejects_const_fn_and_unknown_skip() {
+        let err = impl_hello_fn(&TraceOptions::default(), syn::parse_quote! { const fn f() {} })
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("const fn"), "{}", err);
+
+        let options = TraceOptions { skip: vec![syn::parse_quote!(b)], ..Default::default() };
+        let err = impl_hello_fn(&options, syn::parse_quote! { fn f(a: u8) {} }).err().unwrap();
+        assert!(err.to_string().contains("`b` is not an argument of `f`"), "{}", err);
+    }
+}
@@ -0,0 +1,59 @@
+impl ::hello_macro::HelloMacro for Pet {
+    fn write_greeting<__W: ::core::fmt::Write + ?Sized>(
+        __f: &mut __W,
+    ) -> ::core::fmt::Result {
+        match ::hello_macro::locale::current() {
+            ::hello_macro::locale::Locale::En => {
+                ::core::write!(__f, "Hello, Macro! I'm a {}!", "Pet")
+            }
+            ::hello_macro::locale::Locale::Zh => {
+                ::core::write!(__f, "你好，宏！我是{}！", "Pet")
+            }
+        }
+    }
+    fn write_hello<__W: ::core::fmt::Write + ?Sized>(
+        &self,
+        __f: &mut __W,
+    ) -> ::core::fmt::Result {
+        match self {
+            Self::Fish { age: __hello_age, .. } => {
+                ::core::write!(__f, "{} 今年 {} 岁", "Fish", __hello_age)
+            }
+            Self::Bird { 0: __hello_0, .. } => {
+                ::core::write!(__f, "我是一只{:?}", __hello_0)
+            }
+            Self::Rock { .. } => {
+                match ::hello_macro::locale::current() {
+                    ::hello_macro::locale::Locale::En => {
+                        ::core::write!(__f, "Hello, Macro! I'm a {}!", "石头")
+                    }
+                    ::hello_macro::locale::Locale::Zh => {
+                        ::core::write!(__f, "你好，宏！我是{}！", "石头")
+                    }
+                }
+            }
+        }
+    }
+}
+::hello_macro::__register!(Pet, "Pet");
+impl ::hello_macro::HelloMacro for Never {
+    fn write_greeting<__W: ::core::fmt::Write + ?Sized>(
+        __f: &mut __W,
+    ) -> ::core::fmt::Result {
+        match ::hello_macro::locale::current() {
+            ::hello_macro::locale::Locale::En => {
+                ::core::write!(__f, "Hello, Macro! I'm a {}!", "Never")
+            }
+            ::hello_macro::locale::Locale::Zh => {
+                ::core::write!(__f, "你好，宏！我是{}！", "Never")
+            }
+        }
+    }
+    fn write_hello<__W: ::core::fmt::Write + ?Sized>(
+        &self,
+        __f: &mut __W,
+    ) -> ::core::fmt::Result {
+        match *self {}
+    }
+}
+::hello_macro::__register!(Never, "Never");
@@ -0,0 +1,14 @@
+// 枚举：按变体生成 write_hello，变体字段可以出现在问候语中
+
+#[derive(HelloMacro)]
+enum Pet {
+    #[hello(greeting = "{variant} 今年 {age} 岁")]
+    Fish { age: u8, name: String },
+    #[hello(greeting = "我是一只{0:?}")]
+    Bird(&'static str),
+    #[hello(name = "石头")]
+    Rock,
+}
+
+#[derive(HelloMacro)]
+enum Never {}
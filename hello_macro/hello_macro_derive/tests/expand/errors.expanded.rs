@@ -0,0 +1,6 @@
+::core::compile_error! {
+    "unknown placeholder `{nmae}` in greeting; expected one of `{name}`, `{type}`"
+}
+::core::compile_error! {
+    "`HelloMacro` cannot be derived for unions"
+}
@@ -0,0 +1,11 @@
+// 出错的派生展开为 compile_error!，与真实编译时看到的错误一致
+
+#[derive(HelloMacro)]
+#[hello(greeting = "Hi {nmae}")]
+struct Typo;
+
+#[derive(HelloMacro)]
+union Bits {
+    int: u32,
+    float: f32,
+}
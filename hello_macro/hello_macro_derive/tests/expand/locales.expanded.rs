@@ -0,0 +1,73 @@
+impl ::hello_macro::HelloMacro for Dog {
+    fn write_greeting<__W: ::core::fmt::Write + ?Sized>(
+        __f: &mut __W,
+    ) -> ::core::fmt::Result {
+        match ::hello_macro::locale::current() {
+            ::hello_macro::locale::Locale::En => {
+                ::core::write!(__f, "Hello, Macro! I'm a {}!", "Wangcai")
+            }
+            ::hello_macro::locale::Locale::Zh => {
+                ::core::write!(__f, "汪！我是{}（{}）", "旺财", "Dog")
+            }
+        }
+    }
+}
+::hello_macro::__register!(Dog, "Dog");
+impl ::hello_macro::HelloMacro for Cat {
+    fn write_greeting<__W: ::core::fmt::Write + ?Sized>(
+        __f: &mut __W,
+    ) -> ::core::fmt::Result {
+        match ::hello_macro::locale::current() {
+            ::hello_macro::locale::Locale::En => ::core::write!(__f, "Meow, {}!", "Cat"),
+            ::hello_macro::locale::Locale::Zh => {
+                ::core::write!(__f, "Meow, {}!", "小猫")
+            }
+        }
+    }
+}
+::hello_macro::__register!(Cat, "Cat");
+impl ::hello_macro::HelloMacro for Shape {
+    fn write_greeting<__W: ::core::fmt::Write + ?Sized>(
+        __f: &mut __W,
+    ) -> ::core::fmt::Result {
+        match ::hello_macro::locale::current() {
+            ::hello_macro::locale::Locale::En => {
+                ::core::write!(__f, "Hello, Macro! I'm a {}!", "Shape")
+            }
+            ::hello_macro::locale::Locale::Zh => {
+                ::core::write!(__f, "你好，宏！我是{}！", "Shape")
+            }
+        }
+    }
+    fn write_hello<__W: ::core::fmt::Write + ?Sized>(
+        &self,
+        __f: &mut __W,
+    ) -> ::core::fmt::Result {
+        match self {
+            Self::Circle { radius: __hello_radius, .. } => {
+                match ::hello_macro::locale::current() {
+                    ::hello_macro::locale::Locale::En => {
+                        ::core::write!(__f, "{} r={}", "Circle", __hello_radius)
+                    }
+                    ::hello_macro::locale::Locale::Zh => {
+                        ::core::write!(__f, "圆形，半径 {}", __hello_radius)
+                    }
+                }
+            }
+            Self::Point { .. } => {
+                match ::hello_macro::locale::current() {
+                    ::hello_macro::locale::Locale::En => {
+                        ::core::write!(__f, "Hello, Macro! I'm a {}!", "a point")
+                    }
+                    ::hello_macro::locale::Locale::Zh => {
+                        ::core::write!(
+                            __f, "你好，宏！我是{}！",
+                            ::core::format_args!("{}::{}", "Shape", "Point")
+                        )
+                    }
+                }
+            }
+        }
+    }
+}
+::hello_macro::__register!(Shape, "Shape");
@@ -0,0 +1,17 @@
+// 按语言定制：语言专属的设置优先，其次是不分语言的设置，最后是内置的默认问候语
+
+#[derive(HelloMacro)]
+#[hello(zh(greeting = "汪！我是{name}（{type}）", name = "旺财"), en(name = "Wangcai"))]
+struct Dog;
+
+#[derive(HelloMacro)]
+#[hello(greeting = "Meow, {name}!", zh(name = "小猫"))]
+struct Cat;
+
+#[derive(HelloMacro)]
+enum Shape {
+    #[hello(greeting = "{variant} r={radius}", zh(greeting = "圆形，半径 {radius}"))]
+    Circle { radius: f64 },
+    #[hello(en(name = "a point"))]
+    Point,
+}
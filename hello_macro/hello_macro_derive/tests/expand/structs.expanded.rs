@@ -0,0 +1,64 @@
+impl ::hello_macro::HelloMacro for Cat {
+    fn write_greeting<__W: ::core::fmt::Write + ?Sized>(
+        __f: &mut __W,
+    ) -> ::core::fmt::Result {
+        match ::hello_macro::locale::current() {
+            ::hello_macro::locale::Locale::En => {
+                ::core::write!(__f, "Hello, Macro! I'm a {}!", "Cat")
+            }
+            ::hello_macro::locale::Locale::Zh => {
+                ::core::write!(__f, "你好，宏！我是{}！", "Cat")
+            }
+        }
+    }
+}
+::hello_macro::__register!(Cat, "Cat");
+impl ::hello_macro::HelloMacro for Dog {
+    fn write_greeting<__W: ::core::fmt::Write + ?Sized>(
+        __f: &mut __W,
+    ) -> ::core::fmt::Result {
+        ::core::write!(__f, "Woof! 我是 {}（{}）", "旺财", "Dog")
+    }
+}
+::hello_macro::__register!(Dog, "Dog");
+impl<'a, T: Default> ::hello_macro::HelloMacro for Wrapper<'a, T>
+where
+    T: Send,
+    T: Clone,
+{
+    fn write_greeting<__W: ::core::fmt::Write + ?Sized>(
+        __f: &mut __W,
+    ) -> ::core::fmt::Result {
+        match ::hello_macro::locale::current() {
+            ::hello_macro::locale::Locale::En => {
+                ::core::write!(
+                    __f, "Hello, Macro! I'm a {}!", ::hello_macro::TypeName::of:: < Self
+                    > ()
+                )
+            }
+            ::hello_macro::locale::Locale::Zh => {
+                ::core::write!(
+                    __f, "你好，宏！我是{}！", ::hello_macro::TypeName::of:: <
+                    Self > ()
+                )
+            }
+        }
+    }
+}
+mod zoo {
+    impl crate::hello::HelloMacro for Panda {
+        fn write_greeting<__W: ::core::fmt::Write + ?Sized>(
+            __f: &mut __W,
+        ) -> ::core::fmt::Result {
+            match crate::hello::locale::current() {
+                crate::hello::locale::Locale::En => {
+                    ::core::write!(__f, "Hello, Macro! I'm a {}!", "Panda")
+                }
+                crate::hello::locale::Locale::Zh => {
+                    ::core::write!(__f, "你好，宏！我是{}！", "Panda")
+                }
+            }
+        }
+    }
+    crate::hello::__register!(Panda, "Panda");
+}
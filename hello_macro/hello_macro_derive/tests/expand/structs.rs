@@ -0,0 +1,20 @@
+// 结构体：默认问候语、自定义属性、泛型与内联模块
+
+#[derive(HelloMacro, Debug)]
+struct Cat;
+
+#[derive(HelloMacro)]
+#[hello(greeting = "Woof! 我是 {name}（{type}）", name = "旺财")]
+struct Dog;
+
+#[derive(hello_macro::HelloMacro)]
+#[hello(bound = "T: Clone")]
+struct Wrapper<'a, T: Default>(&'a T)
+where
+    T: Send;
+
+mod zoo {
+    #[derive(HelloMacro)]
+    #[hello(crate = "crate::hello")]
+    pub struct Panda;
+}
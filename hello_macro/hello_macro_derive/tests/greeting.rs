@@ -0,0 +1,72 @@
+// 派生出的问候语可以写入任意 fmt::Write / io::Write，或直接得到 String
+
+use hello_macro::HelloMacro;
+use hello_macro_derive::HelloMacro;
+
+#[derive(HelloMacro)]
+struct Cat;
+
+#[derive(HelloMacro)]
+#[hello(greeting = "Woof! 我是 {name}（{type}）", name = "旺财")]
+struct Dog;
+
+#[derive(HelloMacro)]
+struct Wrapper<W>(W);
+
+#[allow(dead_code)]
+#[derive(HelloMacro)]
+enum Pet {
+    #[hello(greeting = "{variant} 今年 {age} 岁")]
+    Fish { age: u8 },
+    #[hello(greeting = "我是一只{0}")]
+    Bird(&'static str),
+    Rock,
+}
+
+#[test]
+fn greeting_returns_string() {
+    assert_eq!(Cat::greeting(), "Hello, Macro! I'm a Cat!");
+    assert_eq!(Dog::greeting(), "Woof! 我是 旺财（Dog）");
+    assert_eq!(Wrapper::<u8>::greeting(), "Hello, Macro! I'm a Wrapper<u8>!");
+}
+
+#[test]
+fn write_greeting_appends_to_fmt_write() {
+    let mut out = String::from("> ");
+    Cat::write_greeting(&mut out).unwrap();
+    let dyn_out: &mut dyn std::fmt::Write = &mut out;
+    Dog::write_greeting(dyn_out).unwrap();
+    assert_eq!(out, "> Hello, Macro! I'm a Cat!Woof! 我是 旺财（Dog）");
+}
+
+#[test]
+fn io_write_greeting_writes_bytes() {
+    let mut bytes = Vec::new();
+    Cat::io_write_greeting(&mut bytes).unwrap();
+    Pet::Fish { age: 2 }.io_write_hello(&mut bytes).unwrap();
+    assert_eq!(String::from_utf8(bytes).unwrap(), "Hello, Macro! I'm a Cat!Fish 今年 2 岁");
+}
+
+#[test]
+fn io_errors_are_preserved() {
+    struct Broken;
+    impl std::io::Write for Broken {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::new(std::io::ErrorKind::BrokenPipe, "closed"))
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    let err = Cat::io_write_greeting(&mut Broken).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::BrokenPipe);
+}
+
+#[test]
+fn instance_greetings_follow_variants() {
+    assert_eq!(Pet::Fish { age: 2 }.hello_string(), "Fish 今年 2 岁");
+    assert_eq!(Pet::Bird("鹦鹉").hello_string(), "我是一只鹦鹉");
+    assert_eq!(Pet::Rock.hello_string(), "Hello, Macro! I'm a Pet::Rock!");
+    // 结构体的实例问候语与类型问候语相同
+    assert_eq!(Cat.hello_string(), Cat::greeting());
+}
@@ -0,0 +1,128 @@
+// #[hello_fn] 包裹后的函数行为不变：返回值、`?`、提前 return、方法和 async fn 都照常工作
+
+use std::future::Future;
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+
+use hello_macro_derive::hello_fn;
+
+#[hello_fn]
+fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+#[hello_fn]
+fn parse(s: &str) -> Result<u8, std::num::ParseIntError> {
+    let n: u8 = s.trim().parse()?;
+    Ok(n * 2)
+}
+
+#[hello_fn]
+fn halve(n: u32) -> Option<u32> {
+    if !n.is_multiple_of(2) {
+        return None;
+    }
+    Some(n / 2)
+}
+
+// 没有实现 Debug 的参数也可以使用，输出中显示为 `_`
+struct Secret(u32);
+
+#[hello_fn(name = "unlock", skip(pin))]
+fn check(secret: Secret, pin: u32) -> bool {
+    secret.0 == pin
+}
+
+#[hello_fn]
+fn evens(limit: u32) -> impl Iterator<Item = u32> {
+    (0..limit).step_by(2)
+}
+
+#[hello_fn]
+fn largest<T: PartialOrd + Copy + std::fmt::Debug>(items: &[T]) -> T {
+    let mut max = items[0];
+    for &item in items {
+        if item > max {
+            max = item;
+        }
+    }
+    max
+}
+
+#[derive(Debug, Default)]
+struct Counter {
+    count: u32,
+}
+
+impl Counter {
+    #[hello_fn]
+    fn bump(&mut self, by: u32) -> u32 {
+        self.count += by;
+        self.count
+    }
+
+    #[hello_fn]
+    fn get(&self) -> u32 {
+        self.count
+    }
+
+    #[hello_fn]
+    async fn bump_later(&mut self, by: u32) -> u32 {
+        ready(()).await;
+        self.bump(by)
+    }
+}
+
+#[hello_fn]
+async fn double_later(n: u32) -> Result<u32, String> {
+    let n = ready(n).await;
+    if n > 100 {
+        return Err(format!("{} is too large", n));
+    }
+    Ok(n * 2)
+}
+
+async fn ready<T>(value: T) -> T {
+    value
+}
+
+/// 测试用的最简执行器：这里的 future 都不会真正挂起
+fn block_on<F: Future>(fut: F) -> F::Output {
+    let mut fut = pin!(fut);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(out) = fut.as_mut().poll(&mut cx) {
+            return out;
+        }
+    }
+}
+
+#[test]
+fn free_functions_keep_their_results() {
+    assert_eq!(add(1, 2), 3);
+    assert_eq!(parse(" 21 "), Ok(42));
+    assert!(parse("x").is_err());
+    assert_eq!(halve(8), Some(4));
+    assert_eq!(halve(7), None);
+    assert!(check(Secret(7), 7));
+    assert_eq!(evens(7).collect::<Vec<_>>(), [0, 2, 4, 6]);
+    assert_eq!(largest(&[3, 9, 2]), 9);
+}
+
+#[test]
+fn methods_can_borrow_self() {
+    let mut counter = Counter::default();
+    assert_eq!(counter.bump(2), 2);
+    assert_eq!(counter.bump(3), 5);
+    assert_eq!(counter.get(), 5);
+}
+
+#[test]
+fn async_functions_are_traced_when_awaited() {
+    assert_eq!(block_on(double_later(4)), Ok(8));
+    assert_eq!(block_on(double_later(101)), Err("101 is too large".to_string()));
+
+    let mut counter = Counter::default();
+    assert_eq!(block_on(counter.bump_later(4)), 4);
+    assert_eq!(counter.count, 4);
+}
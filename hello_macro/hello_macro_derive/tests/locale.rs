@@ -0,0 +1,51 @@
+// 问候语按 hello_macro::locale::current() 选择语言
+//
+// 语言设置对整个进程生效，因此所有断言放在同一个测试里，避免并行测试互相干扰
+
+use hello_macro::locale::{self, Locale};
+use hello_macro::HelloMacro;
+use hello_macro_derive::HelloMacro;
+
+#[derive(HelloMacro)]
+struct Panda;
+
+#[derive(HelloMacro)]
+#[hello(zh(greeting = "汪！我是{name}（{type}）", name = "旺财"), en(name = "Wangcai"))]
+struct Dog;
+
+#[derive(HelloMacro)]
+#[hello(greeting = "Meow, {name}!", zh(name = "小猫"))]
+struct Cat;
+
+#[allow(dead_code)]
+#[derive(HelloMacro)]
+enum Shape {
+    #[hello(greeting = "{variant} r={radius}", zh(greeting = "圆形，半径 {radius}"))]
+    Circle { radius: f64 },
+    #[hello(en(name = "a point"))]
+    Point,
+    Line,
+}
+
+#[test]
+fn greetings_follow_the_current_locale() {
+    assert_eq!(locale::current(), Locale::DEFAULT);
+
+    locale::set(Locale::En);
+    assert_eq!(Panda::greeting(), "Hello, Macro! I'm a Panda!");
+    assert_eq!(Dog::greeting(), "Hello, Macro! I'm a Wangcai!");
+    assert_eq!(Cat::greeting(), "Meow, Cat!");
+    assert_eq!(Shape::Circle { radius: 1.5 }.hello_string(), "Circle r=1.5");
+    assert_eq!(Shape::Point.hello_string(), "Hello, Macro! I'm a a point!");
+    assert_eq!(Shape::Line.hello_string(), "Hello, Macro! I'm a Shape::Line!");
+
+    locale::set(Locale::Zh);
+    assert_eq!(locale::current(), Locale::Zh);
+    assert_eq!(Panda::greeting(), "你好，宏！我是Panda！");
+    assert_eq!(Dog::greeting(), "汪！我是旺财（Dog）");
+    assert_eq!(Cat::greeting(), "Meow, 小猫!");
+    assert_eq!(Shape::Circle { radius: 1.5 }.hello_string(), "圆形，半径 1.5");
+    assert_eq!(Shape::Point.hello_string(), "你好，宏！我是Shape::Point！");
+
+    locale::set(Locale::DEFAULT);
+}
@@ -0,0 +1,76 @@
+// #[derive(Reflect)] 生成的结构描述
+
+use std::marker::PhantomData;
+
+use hello_macro::{FieldInfo, Kind, Reflect, VariantKind};
+use hello_macro_derive::Reflect;
+
+#[allow(dead_code)]
+#[derive(Reflect)]
+struct Cat {
+    name: String,
+    r#type: &'static str,
+    friends: Vec<Option<Box<Cat>>>,
+}
+
+#[allow(dead_code)]
+#[derive(Reflect)]
+struct Meters(f64);
+
+#[derive(Reflect)]
+struct Unit;
+
+#[allow(dead_code)]
+#[derive(Reflect)]
+enum Pet {
+    Fish { age: u8 },
+    Bird(&'static str, [u8; 4]),
+    Rock,
+}
+
+#[allow(dead_code)]
+#[derive(Reflect)]
+struct Wrapper<'a, T: Clone, const N: usize> {
+    items: [&'a T; N],
+    marker: PhantomData<T>,
+}
+
+#[test]
+fn describes_named_struct() {
+    let info = Cat::TYPE_INFO;
+    assert_eq!(info.name, "Cat");
+    assert_eq!(info.kind, Kind::Struct);
+    assert_eq!(info.field_names().collect::<Vec<_>>(), ["name", "type", "friends"]);
+    assert_eq!(info.field("friends").map(|f| f.ty), Some("Vec<Option<Box<Cat>>>"));
+    assert!(info.variants.is_empty());
+}
+
+#[test]
+fn describes_tuple_and_unit_structs() {
+    assert_eq!(Meters::TYPE_INFO.kind, Kind::TupleStruct);
+    assert_eq!(Meters::TYPE_INFO.fields, &[FieldInfo { name: "0", ty: "f64" }]);
+    assert_eq!(Unit::TYPE_INFO.kind, Kind::UnitStruct);
+    assert!(Unit::TYPE_INFO.fields.is_empty());
+}
+
+#[test]
+fn describes_enum_variants() {
+    let info = Pet::TYPE_INFO;
+    assert_eq!(info.kind, Kind::Enum);
+    assert_eq!(info.variant_names().collect::<Vec<_>>(), ["Fish", "Bird", "Rock"]);
+    let bird = info.variant("Bird").unwrap();
+    assert_eq!(bird.kind, VariantKind::Tuple);
+    assert_eq!(bird.fields[1].ty, "[u8; 4]");
+    assert_eq!(info.variant("Rock").unwrap().kind, VariantKind::Unit);
+    assert_eq!(
+        info.to_string(),
+        "enum Pet { Fish { age: u8 }, Bird(&'static str, [u8; 4]), Rock }"
+    );
+}
+
+#[test]
+fn generic_types_are_supported() {
+    let info = <Wrapper<'static, String, 2>>::TYPE_INFO;
+    assert_eq!(info.name, "Wrapper");
+    assert_eq!(info.field("items").unwrap().ty, "[&'a T; N]");
+}
@@ -0,0 +1,45 @@
+// #[derive(HelloMacro)] 自动把类型登记到运行时注册表
+
+use hello_macro::{registry, HelloMacro, UnknownType};
+use hello_macro_derive::HelloMacro;
+
+#[derive(HelloMacro)]
+struct Cat;
+
+#[derive(HelloMacro)]
+#[hello(name = "旺财")]
+struct Dog;
+
+#[derive(HelloMacro)]
+struct Wrapper<T>(T);
+
+mod zoo {
+    use hello_macro_derive::HelloMacro;
+
+    #[derive(HelloMacro)]
+    pub struct Panda;
+}
+
+#[test]
+fn lists_registered_types_by_type_name() {
+    let names = registry().names();
+    assert_eq!(names, ["Cat", "Dog", "Panda"]);
+    assert_eq!(registry().len(), 3);
+}
+
+#[test]
+fn generic_types_are_not_registered() {
+    assert!(registry().get("Wrapper").is_none());
+    assert_eq!(Wrapper::<u8>::greeting(), "Hello, Macro! I'm a Wrapper<u8>!");
+    let _ = Wrapper(0u8);
+}
+
+#[test]
+fn calls_by_name() {
+    assert_eq!(registry().greeting("Dog").unwrap(), "Hello, Macro! I'm a 旺财!");
+    assert_eq!(registry().greeting("Panda").unwrap(), zoo::Panda::greeting());
+    assert!(registry().call("Cat").is_ok());
+    let err = registry().call("Unicorn").unwrap_err();
+    assert_eq!(err, UnknownType("Unicorn".to_string()));
+    assert_eq!(err.to_string(), "no type named `Unicorn` derives HelloMacro");
+}
@@ -0,0 +1,13 @@
+// 编译期 UI 测试
+//
+// pass/ 下的用例必须能编译通过；fail/ 下的用例必须编译失败，
+// 并且编译器输出要与同名的 .stderr 文件完全一致，从而固定错误信息的措辞和位置。
+//
+// 修改错误信息后，使用 `TRYBUILD=overwrite cargo test` 重新生成 .stderr 文件。
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/pass/*.rs");
+    t.compile_fail("tests/ui/fail/*.rs");
+}
@@ -0,0 +1,9 @@
+use hello_macro_derive::HelloMacro;
+
+#[derive(HelloMacro)]
+enum Maybe<T> {
+    #[hello(bound = "T: Clone")]
+    Just(T),
+}
+
+fn main() {}
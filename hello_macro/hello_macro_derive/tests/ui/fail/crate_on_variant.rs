@@ -0,0 +1,9 @@
+use hello_macro_derive::HelloMacro;
+
+#[derive(HelloMacro)]
+enum Pet {
+    #[hello(crate = "hello_macro")]
+    Rock,
+}
+
+fn main() {}
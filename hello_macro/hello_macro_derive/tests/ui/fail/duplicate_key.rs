@@ -0,0 +1,8 @@
+use hello_macro_derive::HelloMacro;
+
+#[derive(HelloMacro)]
+#[hello(name = "Tom")]
+#[hello(name = "Kitty")]
+struct Cat;
+
+fn main() {}
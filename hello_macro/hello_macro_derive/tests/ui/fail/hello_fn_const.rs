@@ -0,0 +1,8 @@
+use hello_macro_derive::hello_fn;
+
+#[hello_fn]
+const fn answer() -> u32 {
+    42
+}
+
+fn main() {}
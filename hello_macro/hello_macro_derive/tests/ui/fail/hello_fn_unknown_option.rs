@@ -0,0 +1,8 @@
+use hello_macro_derive::hello_fn;
+
+#[hello_fn(skip(a), level = "debug")]
+fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use hello_macro_derive::HelloMacro;
+
+#[derive(HelloMacro)]
+#[hello(zh = "你好")]
+struct Cat;
+
+fn main() {}
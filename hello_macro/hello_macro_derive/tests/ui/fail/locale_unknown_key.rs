@@ -0,0 +1,7 @@
+use hello_macro_derive::HelloMacro;
+
+#[derive(HelloMacro)]
+#[hello(zh(greeting = "你好", bound = "T: Clone"))]
+struct Cat;
+
+fn main() {}
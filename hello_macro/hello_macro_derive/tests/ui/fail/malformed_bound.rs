@@ -0,0 +1,7 @@
+use hello_macro_derive::HelloMacro;
+
+#[derive(HelloMacro)]
+#[hello(bound = "T Clone")]
+struct Wrapper<T>(T);
+
+fn main() {}
@@ -0,0 +1,7 @@
+use hello_macro_derive::HelloMacro;
+
+#[derive(HelloMacro)]
+#[hello = "Meow"]
+struct Cat;
+
+fn main() {}
@@ -0,0 +1,9 @@
+use hello_macro_derive::Reflect;
+
+#[derive(Reflect)]
+union Bits {
+    int: u32,
+    float: f32,
+}
+
+fn main() {}
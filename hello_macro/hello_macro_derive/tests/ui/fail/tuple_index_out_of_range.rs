@@ -0,0 +1,9 @@
+use hello_macro_derive::HelloMacro;
+
+#[derive(HelloMacro)]
+enum Shape {
+    #[hello(greeting = "{0} x {2}")]
+    Rect(u32, u32),
+}
+
+fn main() {}
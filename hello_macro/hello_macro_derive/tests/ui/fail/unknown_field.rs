@@ -0,0 +1,9 @@
+use hello_macro_derive::HelloMacro;
+
+#[derive(HelloMacro)]
+enum Shape {
+    #[hello(greeting = "radius is {radius}")]
+    Circle { r: f64 },
+}
+
+fn main() {}
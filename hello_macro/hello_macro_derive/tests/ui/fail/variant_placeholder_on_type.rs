@@ -0,0 +1,9 @@
+use hello_macro_derive::HelloMacro;
+
+#[derive(HelloMacro)]
+#[hello(greeting = "I'm {variant}")]
+enum Shape {
+    Point,
+}
+
+fn main() {}
@@ -0,0 +1,26 @@
+// 下游 crate 重新导出 hello_macro 时，用 #[hello(crate = "...")] 指向重新导出的位置
+mod facade {
+    pub mod hello {
+        pub use hello_macro::*;
+    }
+}
+
+use facade::hello::{HelloMacro, Reflect};
+use hello_macro_derive::{HelloMacro, Reflect};
+
+#[derive(HelloMacro, Reflect)]
+#[hello(crate = "crate::facade::hello")]
+struct Fox;
+
+#[derive(HelloMacro)]
+#[hello(crate = "facade::hello", name = "小狐狸")]
+enum Kit<T> {
+    Baby(T),
+}
+
+fn main() {
+    Fox::hello_macro();
+    Kit::Baby(1).hello();
+    assert_eq!(Fox::TYPE_INFO.name, "Fox");
+    assert_eq!(Kit::<u8>::greeting(), "Hello, Macro! I'm a 小狐狸!");
+}
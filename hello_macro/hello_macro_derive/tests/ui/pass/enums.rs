@@ -0,0 +1,35 @@
+use hello_macro::HelloMacro;
+use hello_macro_derive::HelloMacro;
+
+#[derive(HelloMacro)]
+#[hello(name = "图形")]
+enum Shape {
+    Point,
+    #[hello(greeting = "{variant} 的半径是 {radius:.1}")]
+    Circle { radius: f64, center: (f64, f64) },
+    #[hello(greeting = "{name}: {0} x {1} ({0:?})", name = "矩形")]
+    Rect(u32, u32),
+    Line(i32, i32),
+}
+
+#[derive(HelloMacro)]
+#[hello(bound = "T: std::fmt::Debug")]
+enum Maybe<T> {
+    #[hello(greeting = "Just {0:?} in {type}")]
+    Just(T),
+    Nothing,
+}
+
+#[derive(HelloMacro)]
+enum Never {}
+
+fn main() {
+    Shape::hello_macro();
+    Shape::Point.hello();
+    Shape::Circle { radius: 1.0, center: (0.0, 0.0) }.hello();
+    Shape::Rect(2, 3).hello();
+    Shape::Line(0, 1).hello();
+    Maybe::Just(vec![1]).hello();
+    Maybe::<u8>::Nothing.hello();
+    Never::hello_macro();
+}
@@ -0,0 +1,46 @@
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+use hello_macro::HelloMacro;
+use hello_macro_derive::HelloMacro;
+
+#[derive(HelloMacro)]
+struct Wrapper<T>(T);
+
+#[derive(HelloMacro)]
+struct View<'a> {
+    text: &'a str,
+}
+
+#[derive(HelloMacro)]
+struct Buffer<const N: usize> {
+    bytes: [u8; N],
+}
+
+#[derive(HelloMacro)]
+#[hello(greeting = "{name} wraps a {type}")]
+struct Bounded<'a, T: ?Sized, const N: usize>
+where
+    T: 'a,
+{
+    items: [&'a T; N],
+}
+
+#[derive(HelloMacro)]
+#[hello(bound = "T: Debug + Clone, U: Default")]
+struct Pair<T, U> {
+    left: PhantomData<T>,
+    right: PhantomData<U>,
+}
+
+fn main() {
+    Wrapper::<i32>::hello_macro();
+    View::hello_macro();
+    Buffer::<4>::hello_macro();
+    Bounded::<str, 2>::hello_macro();
+    Pair::<String, u8>::hello_macro();
+
+    let _ = (Wrapper(1), View { text: "" }, Buffer::<1> { bytes: [0] });
+    let _ = Bounded::<str, 1> { items: ["x"] };
+    let _ = Pair::<String, u8> { left: PhantomData, right: PhantomData };
+}
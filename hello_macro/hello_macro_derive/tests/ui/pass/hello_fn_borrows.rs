@@ -0,0 +1,48 @@
+// #[hello_fn] 方法可以返回从 `&mut self` / `&self` 借出的引用，
+// 函数体中的 `return` 和 `?` 同样适用
+use hello_macro_derive::hello_fn;
+
+#[derive(Debug, Default)]
+struct Stack {
+    items: Vec<u8>,
+}
+
+impl Stack {
+    #[hello_fn]
+    fn items_mut(&mut self) -> &mut Vec<u8> {
+        &mut self.items
+    }
+
+    #[hello_fn]
+    fn items(&self) -> &Vec<u8> {
+        &self.items
+    }
+
+    #[hello_fn]
+    fn top(&self) -> Option<&u8> {
+        if self.items.is_empty() {
+            return None;
+        }
+        self.items.last()
+    }
+
+    #[hello_fn]
+    fn top_mut(&mut self) -> Result<&mut u8, String> {
+        let top = self.items.last_mut().ok_or("empty stack")?;
+        Ok(top)
+    }
+
+    #[hello_fn]
+    async fn items_later(&mut self) -> &mut Vec<u8> {
+        &mut self.items
+    }
+}
+
+fn main() {
+    let mut stack = Stack::default();
+    stack.items_mut().push(1);
+    *stack.top_mut().unwrap() += 1;
+    assert_eq!(stack.items(), &[2]);
+    assert_eq!(stack.top(), Some(&2));
+    drop(stack.items_later());
+}
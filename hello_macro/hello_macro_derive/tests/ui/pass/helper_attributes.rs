@@ -0,0 +1,23 @@
+use hello_macro::HelloMacro;
+use hello_macro_derive::HelloMacro;
+
+#[derive(HelloMacro)]
+struct Plain;
+
+#[derive(HelloMacro)]
+#[hello(greeting = "Woof! 我是 {name}（{type}），{{转义}}", name = "旺财")]
+struct Dog;
+
+#[derive(HelloMacro)]
+#[hello(name = "Kitty")]
+#[hello(greeting = "Meow, {name}!")]
+enum Cat {
+    Tabby,
+}
+
+fn main() {
+    Plain::hello_macro();
+    Dog::hello_macro();
+    Cat::hello_macro();
+    let _ = Cat::Tabby;
+}
@@ -0,0 +1,66 @@
+// 生成的代码只引用 core，可以在 #![no_std] crate 中使用
+//
+// 这里依赖的 hello_macro 启用了 registry（因而也启用了 std），只检查使用方是 #![no_std] 的情况；
+// 针对关闭 std 的 hello_macro 的检查见 hello_macro/no_std_check
+#![no_std]
+
+use core::fmt::{self, Write};
+
+use hello_macro::HelloMacro;
+use hello_macro_derive::HelloMacro;
+
+#[derive(HelloMacro)]
+struct Sensor;
+
+#[derive(HelloMacro)]
+struct Reading<T>(T);
+
+#[derive(HelloMacro)]
+enum Led {
+    #[hello(greeting = "{variant}: {0}%")]
+    On(u8),
+    Off,
+}
+
+/// 固定大小的缓冲区，代替 String
+struct Buf {
+    bytes: [u8; 64],
+    len: usize,
+}
+
+impl Buf {
+    fn new() -> Self {
+        Buf { bytes: [0; 64], len: 0 }
+    }
+
+    fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.bytes[..self.len]).unwrap()
+    }
+}
+
+impl Write for Buf {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        self.bytes.get_mut(self.len..end).ok_or(fmt::Error)?.copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+fn main() {
+    let mut buf = Buf::new();
+    Sensor::write_greeting(&mut buf).unwrap();
+    assert_eq!(buf.as_str(), "Hello, Macro! I'm a Sensor!");
+
+    let mut buf = Buf::new();
+    Reading::<u8>::write_greeting(&mut buf).unwrap();
+    assert_eq!(buf.as_str(), "Hello, Macro! I'm a Reading<u8>!");
+
+    let mut buf = Buf::new();
+    Led::On(80).write_hello(&mut buf).unwrap();
+    assert_eq!(buf.as_str(), "On: 80%");
+
+    let mut buf = Buf::new();
+    Led::Off.write_hello(&mut buf).unwrap();
+    assert_eq!(buf.as_str(), "Hello, Macro! I'm a Led::Off!");
+}
@@ -0,0 +1,52 @@
+// 以 default-features = false, features = ["derive"] 依赖 hello_macro：
+// hello_macro 本身也是 #![no_std]，派生宏生成的代码一旦引用 std 就无法编译
+#![no_std]
+
+use core::fmt::{self, Write};
+
+use hello_macro::{HelloMacro, Reflect};
+
+#[derive(HelloMacro, Reflect)]
+pub struct Sensor;
+
+#[derive(HelloMacro, Reflect)]
+pub struct Reading<T>(pub T);
+
+#[derive(HelloMacro, Reflect)]
+#[hello(zh(name = "指示灯"))]
+pub enum Led {
+    #[hello(greeting = "{variant}: {0}%")]
+    On(u8),
+    Off,
+}
+
+/// 固定大小的缓冲区，代替 String
+pub struct Buf {
+    bytes: [u8; 64],
+    len: usize,
+}
+
+impl Buf {
+    pub fn new() -> Self {
+        Buf { bytes: [0; 64], len: 0 }
+    }
+
+    pub fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.bytes[..self.len]).unwrap()
+    }
+}
+
+impl Default for Buf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Write for Buf {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        self.bytes.get_mut(self.len..end).ok_or(fmt::Error)?.copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
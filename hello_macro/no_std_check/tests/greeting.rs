@@ -0,0 +1,35 @@
+// 关闭 std 后只剩 write_greeting / write_hello，输出写入固定大小的缓冲区
+
+use hello_macro::locale::{self, Locale};
+use hello_macro::{HelloMacro, Kind, Reflect};
+use hello_macro_no_std_check::{Buf, Led, Reading, Sensor};
+
+fn greeting<T: HelloMacro>() -> Buf {
+    let mut buf = Buf::new();
+    T::write_greeting(&mut buf).unwrap();
+    buf
+}
+
+fn hello<T: HelloMacro>(value: &T) -> Buf {
+    let mut buf = Buf::new();
+    value.write_hello(&mut buf).unwrap();
+    buf
+}
+
+#[test]
+fn derived_code_writes_through_core_fmt() {
+    assert_eq!(greeting::<Sensor>().as_str(), "Hello, Macro! I'm a Sensor!");
+    assert_eq!(greeting::<Reading<u8>>().as_str(), "Hello, Macro! I'm a Reading<u8>!");
+    assert_eq!(hello(&Led::On(80)).as_str(), "On: 80%");
+    assert_eq!(hello(&Led::Off).as_str(), "Hello, Macro! I'm a Led::Off!");
+
+    locale::set(Locale::Zh);
+    assert_eq!(greeting::<Led>().as_str(), "你好，宏！我是指示灯！");
+    locale::set(Locale::En);
+}
+
+#[test]
+fn reflect_works_without_std() {
+    assert_eq!(Led::TYPE_INFO.kind, Kind::Enum);
+    assert_eq!(Reading::<u8>::TYPE_INFO.name, "Reading");
+}
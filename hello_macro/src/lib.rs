@@ -1,25 +1,226 @@
+// 关闭 `std` feature 后以 #![no_std] 编译：trait 只依赖 core::fmt，
+// 依赖标准库的方法和模块（String、io、打印、注册表、调用跟踪）随之移除
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod locale;   // 问候语的语言选择：编译期默认语言 + 运行时切换
+pub mod reflect;  // 类型结构的静态描述，配合 #[derive(Reflect)] 使用
+#[cfg(feature = "registry")]
+pub mod registry; // 运行时注册表，列出所有派生了 HelloMacro 的类型
+#[cfg(feature = "std")]
+pub mod trace;    // #[hello_fn] 的运行时支持：记录函数的进入、参数、返回值和耗时
+
+pub use locale::Locale;
+pub use reflect::{FieldInfo, Kind, Reflect, TypeInfo, VariantInfo, VariantKind};
+#[cfg(feature = "registry")]
+pub use registry::{registry, Registration, Registry, UnknownType};
+
+// 派生宏与同名 trait 位于不同的命名空间，可以用同一个名字导出：
+// `use hello_macro::HelloMacro;` 同时导入 trait 和 #[derive(HelloMacro)]
+#[cfg(feature = "derive")]
+pub use hello_macro_derive::{hello_fn, HelloMacro, Reflect};
+
+/// 派生宏生成的代码所需的内部依赖，不属于公开 API
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "registry")]
+    pub use inventory;
+}
+
+/// 未启用 `registry` feature 时，派生宏生成的登记代码展开为空
+#[cfg(not(feature = "registry"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __register {
+    ($ty:ty, $name:expr) => {};
+}
+
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
+
 /// HelloMacro trait 定义
 ///
-/// 这个 trait 定义了一个简单的行为：打印问候消息。
+/// 这个 trait 定义了一个简单的行为：输出问候消息。
 /// 通过过程宏，我们可以为任意结构体自动实现这个 trait，
 /// 而无需手动编写重复的代码。
 ///
+/// 派生宏只生成 [`HelloMacro::write_greeting`]（枚举还会生成 [`HelloMacro::write_hello`]），
+/// 其余方法都是基于它的默认实现：既可以写入任意 [`fmt::Write`] / `io::Write`，
+/// 也可以直接得到 `String`，`hello_macro()` 则是打印到标准输出的便捷封装。
+///
+/// 关闭 `std` feature 时只保留 `write_greeting` 和 `write_hello`，
+/// 生成的代码也只引用 `core`，可以用在 `#![no_std]` crate 中。
+///
 /// # 示例
 ///
+/// 启用（默认开启的）`derive` feature 后，派生宏与 trait 从同一个路径导入：
+///
 /// ```rust
+/// # #[cfg(all(feature = "derive", feature = "std"))] {
 /// use hello_macro::HelloMacro;
 ///
 /// #[derive(HelloMacro)]
 /// struct MyStruct;
 ///
-/// fn main() {
-///     MyStruct::hello_macro(); // 自动生成的实现
-/// }
+/// # hello_macro::locale::set(hello_macro::Locale::En); // locale-zh feature 会把默认语言改为中文
+/// MyStruct::hello_macro(); // 自动生成的实现
+/// assert_eq!(MyStruct::greeting(), "Hello, Macro! I'm a MyStruct!");
+/// # }
 /// ```
 pub trait HelloMacro {
-    /// 打印问候消息的方法
+    /// 把问候消息写入 `f`
     ///
     /// 这个方法的具体实现由过程宏自动生成，
-    /// 通常会打印包含结构体名称的问候消息。
-    fn hello_macro();
+    /// 通常会写入包含结构体名称的问候消息（不带换行）。
+    fn write_greeting<W: fmt::Write + ?Sized>(f: &mut W) -> fmt::Result;
+
+    #[cfg(feature = "std")]
+    /// 以 `String` 形式返回问候消息
+    fn greeting() -> String {
+        let mut out = String::new();
+        Self::write_greeting(&mut out).expect("writing to a String cannot fail");
+        out
+    }
+
+    #[cfg(feature = "std")]
+    /// 把问候消息写入字节流，例如文件、套接字或 `Vec<u8>`
+    fn io_write_greeting<W: io::Write + ?Sized>(w: &mut W) -> io::Result<()> {
+        let mut adapter = IoAdapter::new(w);
+        let result = Self::write_greeting(&mut adapter);
+        adapter.finish(result)
+    }
+
+    #[cfg(feature = "std")]
+    /// 打印问候消息的方法
+    ///
+    /// 便捷封装：把 [`HelloMacro::greeting`] 打印到标准输出并换行。
+    fn hello_macro() {
+        println!("{}", Self::greeting());
+    }
+
+    /// 通过实例把问候消息写入 `f`
+    ///
+    /// 默认实现直接转发给 [`HelloMacro::write_greeting`]。
+    /// 为枚举派生时，生成的实现会按变体分别输出，
+    /// 并且可以在问候语中使用变体字段的值。
+    fn write_hello<W: fmt::Write + ?Sized>(&self, f: &mut W) -> fmt::Result {
+        Self::write_greeting(f)
+    }
+
+    #[cfg(feature = "std")]
+    /// 以 `String` 形式返回实例的问候消息
+    fn hello_string(&self) -> String {
+        let mut out = String::new();
+        self.write_hello(&mut out).expect("writing to a String cannot fail");
+        out
+    }
+
+    #[cfg(feature = "std")]
+    /// 把实例的问候消息写入字节流
+    fn io_write_hello<W: io::Write + ?Sized>(&self, w: &mut W) -> io::Result<()> {
+        let mut adapter = IoAdapter::new(w);
+        let result = self.write_hello(&mut adapter);
+        adapter.finish(result)
+    }
+
+    #[cfg(feature = "std")]
+    /// 通过实例打印问候消息
+    ///
+    /// 便捷封装：把 [`HelloMacro::hello_string`] 打印到标准输出并换行。
+    fn hello(&self) {
+        println!("{}", self.hello_string());
+    }
+}
+
+/// 把 `io::Write` 适配成 `fmt::Write`，并保留底层的 I/O 错误
+///
+/// `fmt::Error` 不携带任何信息，所以写入失败时先把真正的 `io::Error` 存起来，
+/// 最后由 [`IoAdapter::finish`] 交还给调用方。
+#[cfg(feature = "std")]
+struct IoAdapter<'a, W: io::Write + ?Sized> {
+    inner: &'a mut W,
+    error: Option<io::Error>,
+}
+
+#[cfg(feature = "std")]
+impl<'a, W: io::Write + ?Sized> IoAdapter<'a, W> {
+    fn new(inner: &'a mut W) -> Self {
+        IoAdapter { inner, error: None }
+    }
+
+    fn finish(self, result: fmt::Result) -> io::Result<()> {
+        match (result, self.error) {
+            (_, Some(err)) => Err(err),
+            (Err(_), None) => Err(io::Error::other("formatter error")),
+            (Ok(()), None) => Ok(()),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: io::Write + ?Sized> fmt::Write for IoAdapter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|err| {
+            self.error = Some(err);
+            fmt::Error
+        })
+    }
+}
+
+/// 去掉模块路径后的类型名
+///
+/// [`std::any::type_name`] 返回的名称带有完整的模块路径，
+/// 例如 `hello_world::Wrapper<alloc::string::String>`。
+/// `TypeName` 在格式化时会去掉每一段路径前缀，输出 `Wrapper<String>`，
+/// 派生宏为泛型类型生成的问候语就是用它来显示具体类型的。
+///
+/// # 示例
+///
+/// ```rust
+/// use hello_macro::TypeName;
+///
+/// struct Wrapper<T>(T);
+///
+/// let name = TypeName::of::<Wrapper<String>>().to_string();
+/// assert_eq!(name, "Wrapper<String>");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypeName(&'static str);
+
+impl TypeName {
+    /// 获取类型 `T` 的名称
+    pub fn of<T: ?Sized>() -> Self {
+        TypeName(core::any::type_name::<T>())
+    }
+
+    /// 编译器给出的完整名称（包含模块路径）
+    pub fn full(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl fmt::Display for TypeName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // 逐段扫描：遇到 `::` 丢弃前面的路径段，遇到分隔符则输出已扫描的部分
+        let name = self.0;
+        let bytes = name.as_bytes();
+        let mut start = 0;
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b':' if bytes.get(i + 1) == Some(&b':') => {
+                    i += 2;
+                    start = i;
+                    continue;
+                }
+                b'<' | b'>' | b',' | b'(' | b')' | b'[' | b']' | b';' | b'&' | b'*' | b' ' => {
+                    f.write_str(&name[start..=i])?;
+                    start = i + 1;
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        f.write_str(&name[start..])
+    }
 }
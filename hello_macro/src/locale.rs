@@ -0,0 +1,115 @@
+//! 问候语的语言选择
+//!
+//! 派生宏为每种语言各生成一份问候语：默认问候语来自内置的消息目录，
+//! 也可以通过 `#[hello(zh(...), en(...))]` 为单个类型或变体分别定制。
+//! 输出时使用哪种语言由 [`current`] 在运行时决定：
+//!
+//! * 默认语言在编译期确定：启用 `locale-zh` feature 时为中文，否则为英文；
+//! * 运行时可以用 [`set`] 切换（对整个进程生效），
+//!   或者用 [`Locale::from_env`] 读取 `LC_ALL` / `LC_MESSAGES` / `LANG`。
+//!
+//! ```rust
+//! # #[cfg(all(feature = "derive", feature = "std"))] {
+//! use hello_macro::locale::{self, Locale};
+//! use hello_macro::HelloMacro;
+//!
+//! #[derive(HelloMacro)]
+//! #[hello(zh(greeting = "喵～ 我是{name}！", name = "小猫"))]
+//! struct Cat;
+//!
+//! locale::set(Locale::Zh);
+//! assert_eq!(Cat::greeting(), "喵～ 我是小猫！");
+//!
+//! locale::set(Locale::En);
+//! assert_eq!(Cat::greeting(), "Hello, Macro! I'm a Cat!");
+//! # }
+//! ```
+
+use core::fmt;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+/// 支持的语言
+///
+/// 派生宏生成的代码会对所有语言做穷尽匹配，因此新增语言时需要同时更新派生宏中的消息目录
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Locale {
+    /// 英文，默认问候语为 `Hello, Macro! I'm a {name}!`
+    En,
+    /// 中文，默认问候语为 `你好，宏！我是{name}！`
+    Zh,
+}
+
+impl Locale {
+    /// 所有支持的语言
+    pub const ALL: [Locale; 2] = [Locale::En, Locale::Zh];
+
+    /// 编译期确定的默认语言：启用 `locale-zh` feature 时为 [`Locale::Zh`]，否则为 [`Locale::En`]
+    pub const DEFAULT: Locale = if cfg!(feature = "locale-zh") { Locale::Zh } else { Locale::En };
+
+    /// 语言代码，也是 `#[hello(...)]` 中对应的键，例如 `"zh"`
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Zh => "zh",
+        }
+    }
+
+    /// 根据语言代码查找语言，忽略大小写以及地区、编码后缀
+    ///
+    /// `"zh"`、`"zh-CN"`、`"zh_CN.UTF-8"` 都会得到 [`Locale::Zh`]；不支持的语言返回 `None`
+    ///
+    /// ```rust
+    /// use hello_macro::Locale;
+    ///
+    /// assert_eq!(Locale::from_code("zh_CN.UTF-8"), Some(Locale::Zh));
+    /// assert_eq!(Locale::from_code("EN-us"), Some(Locale::En));
+    /// assert_eq!(Locale::from_code("fr"), None);
+    /// ```
+    pub fn from_code(code: &str) -> Option<Locale> {
+        let lang = code.split(['-', '_', '.', '@']).next().unwrap_or("");
+        Locale::ALL.into_iter().find(|locale| lang.eq_ignore_ascii_case(locale.code()))
+    }
+
+    /// 从环境变量推断语言，依次检查 `LC_ALL`、`LC_MESSAGES` 和 `LANG`
+    ///
+    /// 取第一个非空的变量；它指定的语言不受支持时返回 `None`
+    #[cfg(feature = "std")]
+    pub fn from_env() -> Option<Locale> {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|key| std::env::var(key).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Locale::from_code(&value))
+    }
+
+    fn from_index(index: u8) -> Locale {
+        Locale::ALL.get(usize::from(index)).copied().unwrap_or(Locale::DEFAULT)
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale::DEFAULT
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// 当前语言在 [`Locale::ALL`] 中的下标
+///
+/// 只做普通的读写，不需要比较交换，因此在没有原子 CAS 指令的嵌入式目标上也可以使用
+static CURRENT: AtomicU8 = AtomicU8::new(Locale::DEFAULT as u8);
+
+/// 当前使用的语言
+pub fn current() -> Locale {
+    Locale::from_index(CURRENT.load(Ordering::Relaxed))
+}
+
+/// 切换语言，对整个进程（包括其他线程）立即生效
+pub fn set(locale: Locale) {
+    CURRENT.store(locale as u8, Ordering::Relaxed);
+}
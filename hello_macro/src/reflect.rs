@@ -0,0 +1,178 @@
+//! 类型结构的静态描述
+//!
+//! 通过 `#[derive(Reflect)]`，派生宏会在编译期把类型的结构（名称、种类、
+//! 字段名、字段类型、变体列表）写成一个常量 [`TypeInfo`]。
+//! 调试面板、管理后台之类的工具可以直接读取它，而不需要手工维护一张结构表。
+//!
+//! 所有数据都是 `&'static str` 和静态切片，读取时没有任何运行时开销。
+
+use core::fmt;
+
+/// 能够描述自身结构的类型
+///
+/// 通常不需要手动实现，使用 `#[derive(Reflect)]` 即可。
+///
+/// # 示例
+///
+/// ```rust
+/// # #[cfg(feature = "derive")] {
+/// use hello_macro::{Kind, Reflect};
+///
+/// #[derive(Reflect)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// assert_eq!(Point::TYPE_INFO.kind, Kind::Struct);
+/// assert_eq!(Point::TYPE_INFO.field_names().collect::<Vec<_>>(), ["x", "y"]);
+/// assert_eq!(Point::TYPE_INFO.to_string(), "struct Point { x: i32, y: i32 }");
+/// # }
+/// ```
+pub trait Reflect {
+    /// 类型结构的静态描述
+    const TYPE_INFO: TypeInfo;
+}
+
+/// 类型的种类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Kind {
+    /// 具名字段结构体：`struct Cat { name: String }`
+    Struct,
+    /// 元组结构体：`struct Wrapper(i32)`
+    TupleStruct,
+    /// 单元结构体：`struct Cat;`
+    UnitStruct,
+    /// 枚举：`enum Pet { ... }`
+    Enum,
+}
+
+/// 枚举变体的种类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VariantKind {
+    /// 具名字段变体：`Fish { age: u8 }`
+    Struct,
+    /// 元组变体：`Bird(&'static str)`
+    Tuple,
+    /// 单元变体：`Rock`
+    Unit,
+}
+
+/// 一个字段的描述
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FieldInfo {
+    /// 字段名；元组字段为其下标，例如 `"0"`
+    pub name: &'static str,
+    /// 源代码中书写的字段类型，例如 `"Vec<String>"`
+    pub ty: &'static str,
+}
+
+/// 一个枚举变体的描述
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VariantInfo {
+    /// 变体名
+    pub name: &'static str,
+    /// 变体种类
+    pub kind: VariantKind,
+    /// 变体的字段
+    pub fields: &'static [FieldInfo],
+}
+
+/// 一个类型的完整描述
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TypeInfo {
+    /// 类型名（不含泛型参数）
+    pub name: &'static str,
+    /// 类型种类
+    pub kind: Kind,
+    /// 结构体的字段；枚举为空
+    pub fields: &'static [FieldInfo],
+    /// 枚举的变体；结构体为空
+    pub variants: &'static [VariantInfo],
+}
+
+impl TypeInfo {
+    /// 按名称查找结构体字段
+    pub fn field(&self, name: &str) -> Option<&'static FieldInfo> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+
+    /// 按名称查找枚举变体
+    pub fn variant(&self, name: &str) -> Option<&'static VariantInfo> {
+        self.variants.iter().find(|v| v.name == name)
+    }
+
+    /// 结构体的所有字段名
+    pub fn field_names(&self) -> impl Iterator<Item = &'static str> {
+        self.fields.iter().map(|f| f.name)
+    }
+
+    /// 枚举的所有变体名
+    pub fn variant_names(&self) -> impl Iterator<Item = &'static str> {
+        self.variants.iter().map(|v| v.name)
+    }
+}
+
+/// 以接近源代码的形式输出类型结构，例如 `enum Pet { Fish { age: u8 }, Rock }`
+impl fmt::Display for TypeInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            Kind::Struct => {
+                write!(f, "struct {} ", self.name)?;
+                write_named_fields(f, self.fields)
+            }
+            Kind::TupleStruct => {
+                write!(f, "struct {}", self.name)?;
+                write_tuple_fields(f, self.fields)
+            }
+            Kind::UnitStruct => write!(f, "struct {}", self.name),
+            Kind::Enum => {
+                write!(f, "enum {} {{", self.name)?;
+                for (i, variant) in self.variants.iter().enumerate() {
+                    f.write_str(if i == 0 { " " } else { ", " })?;
+                    write!(f, "{}", variant)?;
+                }
+                f.write_str(if self.variants.is_empty() { "}" } else { " }" })
+            }
+        }
+    }
+}
+
+impl fmt::Display for VariantInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name)?;
+        match self.kind {
+            VariantKind::Struct => {
+                f.write_str(" ")?;
+                write_named_fields(f, self.fields)
+            }
+            VariantKind::Tuple => write_tuple_fields(f, self.fields),
+            VariantKind::Unit => Ok(()),
+        }
+    }
+}
+
+fn write_named_fields(f: &mut fmt::Formatter<'_>, fields: &[FieldInfo]) -> fmt::Result {
+    if fields.is_empty() {
+        return f.write_str("{}");
+    }
+    f.write_str("{ ")?;
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        write!(f, "{}: {}", field.name, field.ty)?;
+    }
+    f.write_str(" }")
+}
+
+fn write_tuple_fields(f: &mut fmt::Formatter<'_>, fields: &[FieldInfo]) -> fmt::Result {
+    f.write_str("(")?;
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        f.write_str(field.ty)?;
+    }
+    f.write_str(")")
+}
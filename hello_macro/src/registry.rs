@@ -0,0 +1,160 @@
+//! 运行时注册表：列出并按名称调用所有派生了 HelloMacro 的类型
+//!
+//! `#[derive(HelloMacro)]` 会为每个非泛型类型生成一条 [`Registration`]，
+//! 通过 [`inventory`] 在链接期收集起来。因此不需要手工维护
+//! `match name { "Cat" => Cat::hello_macro(), ... }` 这样的分发表，
+//! 新增类型只要加上派生即可出现在注册表中。
+//!
+//! 泛型类型（如 `Wrapper<T>`）没有唯一的具体类型，不会被登记。
+//!
+//! # 示例
+//!
+//! ```rust
+//! # #[cfg(feature = "derive")] {
+//! use hello_macro::HelloMacro;
+//!
+//! #[derive(HelloMacro)]
+//! struct Cat;
+//!
+//! # hello_macro::locale::set(hello_macro::Locale::En);
+//! let registry = hello_macro::registry();
+//! assert!(registry.names().contains(&"Cat"));
+//! assert_eq!(registry.greeting("Cat").unwrap(), "Hello, Macro! I'm a Cat!");
+//! registry.call("Cat").unwrap(); // 打印 "Hello, Macro! I'm a Cat!"
+//! assert!(registry.call("Unicorn").is_err());
+//! # }
+//! ```
+
+use std::{error, fmt};
+
+use crate::HelloMacro;
+
+/// 一个已登记的类型
+pub struct Registration {
+    name: &'static str,
+    write_greeting: fn(&mut dyn fmt::Write) -> fmt::Result,
+}
+
+impl Registration {
+    /// 为类型 `T` 创建登记项，`name` 是在注册表中查找时使用的名称
+    ///
+    /// 通常由派生宏生成的代码调用，不需要手动使用。
+    pub const fn new<T: HelloMacro>(name: &'static str) -> Self {
+        Registration {
+            name,
+            write_greeting: write_greeting_of::<T>,
+        }
+    }
+
+    /// 登记时使用的名称（类型名）
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// 把该类型的问候消息写入 `f`
+    pub fn write_greeting(&self, f: &mut dyn fmt::Write) -> fmt::Result {
+        (self.write_greeting)(f)
+    }
+
+    /// 以 `String` 形式返回该类型的问候消息
+    pub fn greeting(&self) -> String {
+        let mut out = String::new();
+        self.write_greeting(&mut out).expect("writing to a String cannot fail");
+        out
+    }
+
+    /// 打印该类型的问候消息，相当于调用 `T::hello_macro()`
+    pub fn hello(&self) {
+        println!("{}", self.greeting());
+    }
+}
+
+/// 把 `T::write_greeting` 固定为函数指针所需的 `&mut dyn fmt::Write` 形式
+fn write_greeting_of<T: HelloMacro>(f: &mut dyn fmt::Write) -> fmt::Result {
+    T::write_greeting(f)
+}
+
+impl fmt::Debug for Registration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Registration").field("name", &self.name).finish()
+    }
+}
+
+inventory::collect!(Registration);
+
+/// 按名称查找失败时返回的错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownType(pub String);
+
+impl fmt::Display for UnknownType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no type named `{}` derives HelloMacro", self.0)
+    }
+}
+
+impl error::Error for UnknownType {}
+
+/// 注册表的访问入口，通过 [`registry()`] 获取
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Registry {
+    _private: (),
+}
+
+/// 获取全局注册表
+pub fn registry() -> Registry {
+    Registry { _private: () }
+}
+
+impl Registry {
+    /// 所有已登记的类型，按名称排序
+    pub fn iter(&self) -> impl Iterator<Item = &'static Registration> {
+        let mut all: Vec<_> = inventory::iter::<Registration>.into_iter().collect();
+        all.sort_by_key(|r| r.name);
+        all.into_iter()
+    }
+
+    /// 所有已登记的类型名，按名称排序
+    pub fn names(&self) -> Vec<&'static str> {
+        self.iter().map(Registration::name).collect()
+    }
+
+    /// 已登记的类型数量
+    pub fn len(&self) -> usize {
+        inventory::iter::<Registration>.into_iter().count()
+    }
+
+    /// 注册表是否为空
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 按名称查找；不同模块中的同名类型都会登记，此时返回排序后的第一个
+    pub fn get(&self, name: &str) -> Option<&'static Registration> {
+        self.iter().find(|r| r.name == name)
+    }
+
+    /// 按名称返回问候消息
+    pub fn greeting(&self, name: &str) -> Result<String, UnknownType> {
+        self.get(name)
+            .map(Registration::greeting)
+            .ok_or_else(|| UnknownType(name.to_string()))
+    }
+
+    /// 按名称打印问候消息
+    pub fn call(&self, name: &str) -> Result<(), UnknownType> {
+        self.get(name)
+            .map(Registration::hello)
+            .ok_or_else(|| UnknownType(name.to_string()))
+    }
+}
+
+/// 派生宏生成的登记代码，未启用 `registry` feature 时展开为空
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __register {
+    ($ty:ty, $name:expr) => {
+        $crate::__private::inventory::submit! {
+            $crate::registry::Registration::new::<$ty>($name)
+        }
+    };
+}
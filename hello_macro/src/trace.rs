@@ -0,0 +1,160 @@
+//! `#[hello_fn]` 的运行时支持
+//!
+//! 属性宏 `#[hello_fn]` 会把函数体包裹起来：进入函数时用 [`Call::enter`]
+//! 记录函数名和参数，返回时用 [`Call::exit`] 记录返回值和耗时。
+//! 输出写到标准错误，不会和程序本身打印到标准输出的内容混在一起：
+//!
+//! ```text
+//! [hello_fn] → add(a = 1, b = 2)
+//! [hello_fn] ← add = 3 (1.2µs)
+//! ```
+//!
+//! 没有实现 `Debug` 的参数和返回值显示为 `_`。
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// 一次被跟踪的函数调用
+///
+/// 通常不需要直接使用，由 `#[hello_fn]` 生成的代码创建。
+///
+/// # 示例
+///
+/// ```rust
+/// use hello_macro::trace::Call;
+///
+/// let call = Call::enter("add", &[("a", &1), ("b", &2)]);
+/// let ret = 1 + 2;
+/// call.exit(&ret);
+/// ```
+#[must_use = "调用 `exit` 才会记录返回值和耗时"]
+pub struct Call {
+    name: &'static str,
+    start: Instant,
+}
+
+impl Call {
+    /// 记录函数的进入及其参数，并开始计时
+    pub fn enter(name: &'static str, args: &[(&str, &dyn fmt::Debug)]) -> Self {
+        eprintln!("[hello_fn] → {}", Enter { name, args });
+        Call { name, start: Instant::now() }
+    }
+
+    /// 记录返回值和从进入到现在的耗时
+    pub fn exit(self, ret: &dyn fmt::Debug) {
+        eprintln!("[hello_fn] ← {}", Exit { name: self.name, ret, elapsed: self.start.elapsed() });
+    }
+}
+
+/// 进入函数时的一行输出：`add(a = 1, b = 2)`
+struct Enter<'a> {
+    name: &'a str,
+    args: &'a [(&'a str, &'a dyn fmt::Debug)],
+}
+
+impl fmt::Display for Enter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}(", self.name)?;
+        for (i, (name, value)) in self.args.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{} = {:?}", name, value)?;
+        }
+        f.write_str(")")
+    }
+}
+
+/// 离开函数时的一行输出：`add = 3 (1.2µs)`
+struct Exit<'a> {
+    name: &'a str,
+    ret: &'a dyn fmt::Debug,
+    elapsed: Duration,
+}
+
+impl fmt::Display for Exit<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} = {:?} ({:?})", self.name, self.ret, self.elapsed)
+    }
+}
+
+/// 把任意值转成 `&dyn Debug`：实现了 `Debug` 的类型按原样输出，其余输出 `_`
+///
+/// 过程宏看不到类型信息，无法判断参数是否实现了 `Debug`，
+/// 这里借助方法解析的自动引用顺序（"autoref specialization"）在编译期做选择：
+/// `(&DebugArg(&x)).__hello_debug()` 优先匹配 [`ViaDebug`]（要求 `T: Debug`），
+/// 匹配不上时才多加一层引用，落到 [`ViaOpaque`]。
+///
+/// 对于泛型参数，选择发生在函数定义处：只有声明了 `T: Debug` 约束时才会输出具体的值。
+#[doc(hidden)]
+pub struct DebugArg<'a, T: ?Sized>(pub &'a T);
+
+#[doc(hidden)]
+pub trait ViaDebug {
+    fn __hello_debug(&self) -> &dyn fmt::Debug;
+}
+
+impl<T: fmt::Debug> ViaDebug for DebugArg<'_, T> {
+    fn __hello_debug(&self) -> &dyn fmt::Debug {
+        self.0
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaOpaque {
+    fn __hello_debug(&self) -> &dyn fmt::Debug;
+}
+
+impl<T: ?Sized> ViaOpaque for &DebugArg<'_, T> {
+    fn __hello_debug(&self) -> &dyn fmt::Debug {
+        &Opaque
+    }
+}
+
+/// 没有实现 `Debug` 的值的占位输出
+struct Opaque;
+
+impl fmt::Debug for Opaque {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Secret;
+
+    #[test]
+    fn enter_lists_the_arguments_in_order() {
+        let args: [(&str, &dyn fmt::Debug); 2] = [("a", &1), ("s", &"hi")];
+        assert_eq!(Enter { name: "add", args: &args }.to_string(), r#"add(a = 1, s = "hi")"#);
+        assert_eq!(Enter { name: "now", args: &[] }.to_string(), "now()");
+    }
+
+    #[test]
+    fn exit_shows_the_return_value_and_elapsed_time() {
+        let exit = Exit { name: "add", ret: &Some(3), elapsed: Duration::from_micros(1200) };
+        assert_eq!(exit.to_string(), "add = Some(3) (1.2ms)");
+        let unit = Exit { name: "log", ret: &(), elapsed: Duration::from_nanos(800) };
+        assert_eq!(unit.to_string(), "log = () (800ns)");
+    }
+
+    #[test]
+    #[allow(clippy::needless_borrow)] // 与生成的代码写法一致，这层引用正是选择 ViaDebug / ViaOpaque 的关键
+    fn values_without_debug_are_shown_as_underscore() {
+        // 与生成的代码相同，临时值只活到语句结束，所以整行写在一个表达式里
+        let secret = Secret;
+        let line = Enter {
+            name: "unlock",
+            args: &[("n", (&DebugArg(&7)).__hello_debug()), ("secret", (&DebugArg(&secret)).__hello_debug())],
+        }
+        .to_string();
+        assert_eq!(line, "unlock(n = 7, secret = _)");
+
+        let line =
+            Exit { name: "unlock", ret: (&DebugArg(&secret)).__hello_debug(), elapsed: Duration::ZERO }.to_string();
+        assert_eq!(line, "unlock = _ (0ns)");
+    }
+}
@@ -1,10 +1,7 @@
-// 引入外部 crate：hello_macro（包含 trait 定义）和 hello_macro_derive（包含过程宏实现）
-extern crate hello_macro;
-#[macro_use]
-extern crate hello_macro_derive;
-
-// 导入 HelloMacro trait，以便可以使用其方法
-use hello_macro::HelloMacro;
+// 导入 HelloMacro / Reflect：同一个名字既是 trait，也是同名的派生宏
+// （派生宏由 hello_macro 的 `derive` 特性重新导出）
+use hello_macro::locale::{self, Locale};
+use hello_macro::{hello_fn, HelloMacro, Reflect};
 
 // 使用自定义派生宏 HelloMacro 为 Cat 结构体自动实现 HelloMacro trait
 // 这将在编译时生成 impl HelloMacro for Cat 的代码
@@ -12,21 +9,140 @@ use hello_macro::HelloMacro;
 #[derive(Debug)]  // 同时派生 Debug trait，便于调试
 struct Cat;
 
+// 通过 #[hello(...)] 辅助属性自定义问候语
+// greeting 中的 {name} 会被替换为 name 指定的显示名称，{type} 为类型名
+// 占位符在编译期校验，写成 {nmae} 之类会直接编译失败
+// zh(...) 中的设置只在中文环境下生效，其余语言使用外层的 greeting / name
+#[derive(HelloMacro)]
+#[hello(
+    greeting = "Woof! I'm {name} ({type})",
+    name = "Wangcai",
+    zh(greeting = "汪！我是{name}（{type}）", name = "旺财")
+)]
+struct Dog;
+
+// 泛型类型同样可以派生，泛型参数、生命周期和 where 子句都会带到生成的 impl 上
+// 问候语中的名称来自 std::any::type_name，显示单态化之后的具体类型
+#[derive(HelloMacro)]
+struct Wrapper<T>(T);
+
+// 枚举还会生成实例方法 hello(&self)，按变体分别输出
+// 变体上的 #[hello(...)] 可以使用 {variant} 和字段占位符（{0}、{age} 等字段名）
+// 名为 name、type 或 variant 的字段会被同名的内置占位符遮盖，无法在模板中引用
+// 同时派生 Reflect，在编译期生成类型结构的静态描述（字段、类型、变体）
+#[derive(HelloMacro, Reflect)]
+enum Pet {
+    #[hello(greeting = "{variant} 今年 {age} 岁")]
+    Fish { age: u8 },
+    #[hello(greeting = "我是一只{0}")]
+    Bird(&'static str),
+    Rock,
+}
+
+// 属性宏 #[hello_fn] 跟踪函数调用：进入时记录参数，返回时记录返回值和耗时
+// 输出写到标准错误，例如：
+//   [hello_fn] → pet_ages(pets = _)
+//   [hello_fn] ← pet_ages = 2 (1.1µs)
+// Pet 没有实现 Debug，所以参数显示为 `_`
+#[hello_fn]
+fn pet_ages(pets: &[Pet]) -> u32 {
+    pets.iter()
+        .map(|pet| match pet {
+            Pet::Fish { age } => u32::from(*age),
+            _ => 0,
+        })
+        .sum()
+}
+
 /*
-#[derive(HelloMacro)] 属性宏会自动生成以下代码实现：
+#[derive(HelloMacro)] 属性宏会为 Cat 自动生成以下代码：
 
-impl HelloMacro for Cat {
-    fn hello_macro() {
-        println!("Hello, Macro! I'm a Cat!");
+impl ::hello_macro::HelloMacro for Cat {
+    fn write_greeting<__W: ::core::fmt::Write + ?Sized>(
+        __f: &mut __W,
+    ) -> ::core::fmt::Result {
+        match ::hello_macro::locale::current() {
+            ::hello_macro::locale::Locale::En => {
+                ::core::write!(__f, "Hello, Macro! I'm a {}!", "Cat")
+            }
+            ::hello_macro::locale::Locale::Zh => {
+                ::core::write!(__f, "你好，宏！我是{}！", "Cat")
+            }
+        }
     }
 }
+::hello_macro::__register!(Cat, "Cat");
 
-这个实现是由过程宏在编译时自动生成的，无需手动编写。
+派生宏只生成 write_greeting；greeting()、hello_macro() 等方法是 trait 的默认实现，
+都通过 write_greeting 输出。这个实现是由过程宏在编译时自动生成的，无需手动编写。
+查看本文件中所有派生的完整展开结果：
+
+    cd ../hello_macro/hello_macro_derive
+    cargo run --features inspect --bin hello_expand -- ../../hello_world/src/main.rs
 */
 
 // 主函数：程序入口点
+//
+// 不带参数时运行完整演示；也可以通过注册表按名称调用：
+//   cargo run -- --list   列出所有派生了 HelloMacro 的类型
+//   cargo run -- Dog      只调用 Dog 的问候语
 fn main() {
+    // 问候语的语言跟随环境变量（LC_ALL / LC_MESSAGES / LANG），例如 LANG=zh_CN.UTF-8；
+    // 无法识别时使用编译期的默认语言（英文，启用 hello_macro 的 locale-zh feature 后为中文）
+    // 下面注释中的输出都以英文为例
+    if let Some(lang) = Locale::from_env() {
+        locale::set(lang);
+    }
+
+    if let Some(arg) = std::env::args().nth(1) {
+        let registry = hello_macro::registry();
+        if arg == "--list" {
+            for name in registry.names() {
+                println!("{}", name);
+            }
+        } else if let Err(err) = registry.call(&arg) {
+            eprintln!("{}（可用的类型：{}）", err, registry.names().join(", "));
+            std::process::exit(1);
+        }
+        return;
+    }
+
     // 调用自动生成的 hello_macro 方法
     // 这会输出: "Hello, Macro! I'm a Cat!"
     Cat::hello_macro();
+
+    // 也可以拿到 String 自行处理，或写入任意 fmt::Write / io::Write
+    let greeting = Cat::greeting();
+    println!("greeting() 返回了 {} 个字节", greeting.len());
+
+    // 这会输出: "Woof! I'm Wangcai (Dog)"
+    Dog::hello_macro();
+
+    // 运行时切换语言，对整个进程生效
+    // 这会依次输出: "你好，宏！我是Cat！" 和 "汪！我是旺财（Dog）"
+    let previous = locale::current();
+    locale::set(Locale::Zh);
+    Cat::hello_macro();
+    Dog::hello_macro();
+    locale::set(previous);
+
+    // 这会输出: "Hello, Macro! I'm a Wrapper<i32>!"
+    Wrapper::<i32>::hello_macro();
+    let _ = Wrapper(0);
+
+    // 这会依次输出: "Fish 今年 2 岁"、"我是一只鹦鹉" 和 "Hello, Macro! I'm a Pet::Rock!"
+    let pets = [Pet::Fish { age: 2 }, Pet::Bird("鹦鹉"), Pet::Rock];
+    for pet in &pets {
+        pet.hello();
+    }
+
+    // 被 #[hello_fn] 标注的函数照常返回结果，同时在标准错误中留下调用记录
+    println!("宠物的年龄合计: {}", pet_ages(&pets));
+
+    // 这会输出: "enum Pet { Fish { age: u8 }, Bird(&'static str), Rock }"
+    println!("{}", Pet::TYPE_INFO);
+
+    // 所有派生了 HelloMacro 的非泛型类型都会自动登记到注册表，无需手工维护 match
+    // 这会输出: "已登记的类型: Cat, Dog, Pet"
+    println!("已登记的类型: {}", hello_macro::registry().names().join(", "));
 }
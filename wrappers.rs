@@ -129,6 +129,7 @@ fn main() {
 
     // ===== 8. 组合模式演示 =====
     // 常见的组合模式：Rc<RefCell<T>> 提供共享所有权和内部可变性
+    // 完整的泛型图（增删、遍历、拓扑排序、环检测）见 graph/ crate
     println!("8. 组合模式 - Rc<RefCell<T>>:");
 
     #[derive(Debug)]